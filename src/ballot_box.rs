@@ -7,10 +7,35 @@
 use std::collections::HashMap;
 use scrypto::dec;
 use scrypto::math::Decimal;
-use crate::decimal_maths::{cbrt, ln};
-use crate::proposal::{Proposal, ProposalStatus, Vote, Change};
+use scrypto::prelude::ResourceAddress;
+use crate::decimal_maths::{cbrt, ln, EULER_CONST};
+use crate::multiple_choice::{MultipleChoice, ProposalOption, VotingMode};
+use crate::poll::Poll;
+use crate::proposal::{add_delegation, Amendment, Proposal, ProposalMetadata, ProposalStatus, ProposalVersion, Vote, Change};
 use crate::voter_card::VoterCard;
 
+/// Supply of DAO tokens used as a reference to compute the approval of suggestions and to weight votes
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone, PartialEq)]
+pub enum SupplyReference
+{
+    /// All the tokens emitted by the DAO
+    EmittedTokens,
+
+    /// Tokens emitted by the DAO minus the burned ones and the ones held by the treasury
+    CirculatingSupply
+}
+
+/// What happens to the support already given to a proposal when it is amended
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone, PartialEq)]
+pub enum AmendmentRule
+{
+    /// The support is kept
+    KeepSupport,
+
+    /// The support is reset and the supporters can support the new version again
+    ResetSupport
+}
+
 /// A BallotBox is simply a list of proposals and some voting parameters that can be changed by voting
 /// In the future, the voting_power function that computes the voting power associated to a bunch of
 /// tokens, will also be a parameter that can be changed. Unfortunately, Scrypto doesnt not enable us
@@ -34,7 +59,66 @@ pub struct BallotBox
     suggestion_approval_threshold: Decimal,
 
     /// Minimum of votes that should be casted for a vote to be considered legitimate
-    minimum_votes_threshold: Decimal
+    minimum_votes_threshold: Decimal,
+
+    /// Supply used as a reference for new proposals
+    supply_reference: SupplyReference,
+
+    /// VoterCards ids of the veto council members
+    veto_council: Vec<u64>,
+
+    /// Period during which the veto council can veto an accepted proposal
+    veto_period: u64,
+
+    /// Log of the vetoes cast by the veto council
+    veto_log: Vec<VetoAction>,
+
+    /// Maximum amount of each resource that an optimistic proposal can spend
+    optimistic_limits: HashMap<ResourceAddress, Decimal>,
+
+    /// Period during which an optimistic proposal can be objected to
+    challenge_period: u64,
+
+    /// Share of the reference supply whose objections send an optimistic proposal to a vote
+    objection_threshold: Decimal,
+
+    /// Period of the voting phase of fast-track proposals
+    fast_track_vote_period: u64,
+
+    /// Share of the reference supply that should vote for a fast-track proposal to be legitimate
+    fast_track_quorum: Decimal,
+
+    /// Share of the votes that should be for a fast-track proposal for it to be accepted
+    fast_track_supermajority: Decimal,
+
+    /// List of all made polls
+    polls: Vec<Poll>,
+
+    /// Period of the voting window of polls
+    poll_period: u64,
+
+    /// What happens to the support of a proposal when it is amended
+    amendment_rule: AmendmentRule,
+
+    /// Threshold for an amendment suggested by a member to be applied
+    amendment_support_threshold: Decimal,
+
+    /// Period after which an accepted proposal that could not be executed can be marked as failed
+    execution_grace_period: u64
+}
+
+/// A veto cast by a member of the veto council
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct VetoAction
+{
+    /// Id of the vetoed proposal
+    pub proposal_id: usize,
+
+    /// VoterCard id of the council member who cast the veto
+    pub voter_id: u64,
+
+    /// Epoch when the veto was cast
+    pub epoch: u64
 }
 
 impl BallotBox
@@ -58,46 +142,206 @@ impl BallotBox
             vote_period: 168,
             suggestion_approval_threshold: dec!("0.0015"),
             minimum_votes_threshold: Decimal::zero(),
+            supply_reference: SupplyReference::EmittedTokens,
+            veto_council: vec![],
+            veto_period: 48,
+            veto_log: vec![],
+            optimistic_limits: HashMap::new(),
+            challenge_period: 72,
+            objection_threshold: dec!("0.01"),
+            fast_track_vote_period: 24,
+            fast_track_quorum: dec!("0.2"),
+            fast_track_supermajority: dec!("0.75"),
+            polls: vec![],
+            poll_period: 168,
+            amendment_rule: AmendmentRule::ResetSupport,
+            amendment_support_threshold: dec!("0.0015"),
+            execution_grace_period: 168
+        }
+
+    }
+
+    /// Returns the supply that should be used as a reference for new proposals
+    pub fn supply_reference(&self) -> &SupplyReference
+    {
+        &self.supply_reference
+    }
+
+    /// Returns the VoterCards ids of the veto council members
+    pub fn veto_council(&self) -> &Vec<u64>
+    {
+        &self.veto_council
+    }
+
+    /// Returns the log of the vetoes cast by the veto council
+    pub fn veto_log(&self) -> &Vec<VetoAction>
+    {
+        &self.veto_log
+    }
+
+    /// Sets the VoterCard id of the author of a proposal, who can amend it during its suggestion phase
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal
+    /// * `author` - VoterCard id of the author
+    pub fn set_author(&mut self, proposal_id: usize, author: u64)
+    {
+        assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
+        self.proposals[proposal_id].author = Some(author);
+    }
+
+    /// Amends a proposal in suggestion phase on behalf of its author after checking the length of
+    /// its new metadata
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal
+    /// * `author` - VoterCard id of the user amending the Proposal
+    /// * `metadata` - new metadata of the Proposal
+    /// * `changes` - new changes of the Proposal
+    /// * `current_epoch` - current epoch
+    pub fn amend_proposal(&mut self, proposal_id: usize, author: u64, metadata: ProposalMetadata, changes: Vec<Change>, current_epoch: u64)
+    {
+        metadata.check();
+        let reset_support = self.amendment_rule == AmendmentRule::ResetSupport;
+        let proposal = self.get_amendable_proposal(proposal_id, current_epoch);
+        assert_eq!(proposal.author, Some(author), "Only the author can directly amend the proposal");
+
+        proposal.amend(metadata, changes, reset_support, current_epoch);
+    }
+
+    /// Suggests an amendment to a proposal in suggestion phase after checking the length of its
+    /// metadata. The amendment is applied once it has enough support
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal
+    /// * `metadata` - new metadata of the Proposal
+    /// * `changes` - new changes of the Proposal
+    /// * `current_epoch` - current epoch
+    pub fn suggest_amendment(&mut self, proposal_id: usize, metadata: ProposalMetadata, changes: Vec<Change>, current_epoch: u64)
+    {
+        metadata.check();
+        let proposal = self.get_amendable_proposal(proposal_id, current_epoch);
+        let amendment = Amendment
+        {
+            metadata,
+            changes,
+            base_version: proposal.versions.len() - 1,
+            supporting_votes: Decimal::zero(),
+            supporters: vec![],
+            applied: false
+        };
+        proposal.amendments.push(amendment);
+    }
+
+    /// Supports an amendment to a proposal in suggestion phase and applies it if it has enough
+    /// support. An amendment of an older version of the proposal cannot be supported anymore.
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal
+    /// * `amendment_id` - index of the amendment
+    /// * `voter_card` - VoterCard of the user supporting the amendment
+    /// * `current_epoch` - current epoch
+    pub fn support_amendment(&mut self, proposal_id: usize, amendment_id: usize, voter_card: &VoterCard, current_epoch: u64)
+    {
+        let reset_support = self.amendment_rule == AmendmentRule::ResetSupport;
+        let threshold = self.amendment_support_threshold;
+        let proposal = self.get_amendable_proposal(proposal_id, current_epoch);
+        let current_version = proposal.versions.len() - 1;
+
+        let amendment = proposal.amendments.get_mut(amendment_id).expect("This amendment does not exist");
+        assert!(!amendment.applied && amendment.base_version == current_version, "This amendment cannot be applied anymore");
+        assert!(!amendment.supporters.contains(&voter_card.voter_id), "You already supported this amendment");
+
+        amendment.supporters.push(voter_card.voter_id);
+        amendment.supporting_votes = amendment.supporting_votes + voter_card.voting_power(current_epoch);
+
+        if amendment.supporting_votes / proposal.reference_supply >= threshold
+        {
+            amendment.applied = true;
+            let (metadata, changes) = (amendment.metadata.clone(), amendment.changes.clone());
+            proposal.amend(metadata, changes, reset_support, current_epoch);
         }
+    }
+
+    /// Returns the id that the next proposal will have
+    pub fn new_proposal_id(&self) -> usize
+    {
+        self.new_proposal_id
+    }
+
+    /// Returns the poll with the given id
+    ///
+    /// # Arguments
+    /// * `poll_id` - id of the Poll
+    pub fn get_poll(&self, poll_id: usize) -> &Poll
+    {
+        assert!(poll_id < self.polls.len(), "This poll does not exist!");
+        &self.polls[poll_id]
+    }
 
+    /// Returns the proposal with the given id
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal
+    pub fn get_proposal(&self, proposal_id: usize) -> &Proposal
+    {
+        assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
+        &self.proposals[proposal_id]
     }
 
-    /// Creates a new proposal from the given parameters
+    /// Creates a new proposal from the given parameters after checking the length of its metadata
     ///
     /// # Arguments
-    /// * `description` - String describing the Proposal
+    /// * `metadata` - title, summary, discussion URL and content hash of the Proposal
     /// * `suggested_changes` - changes that will be made to the DAO with this proposal
     /// * `current_epoch` - current epoch
+    /// * `reference_supply` - reference supply of DAO tokens
     ///
     /// # Examples
     /// ```
     /// use radix_engine::ledger::TypedInMemorySubstateStore;
     /// use styx::ballot_box::BallotBox;
-    /// use styx::proposal::Change;
+    /// use styx::proposal::{Change, ProposalMetadata};
     /// use scrypto::prelude::dec;
+    /// use scrypto::crypto::Hash;
     /// use scrypto_unit::TestRunner;
     ///
     /// let mut ballet_box = BallotBox::new();
     /// let mut store = TypedInMemorySubstateStore::with_bootstrap();
     /// let mut test_runner = TestRunner::new(true, &mut store);
-    /// ballet_box.make_proposal(String::from("An example Proposal"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(1));
+    /// let metadata = ProposalMetadata { title: String::from("Example"), summary: String::from("An example Proposal"),
+    ///     discussion_url: String::from("https://forum.example.com/proposal"), content_hash: Hash([0; 32]) };
+    /// ballet_box.make_proposal(metadata, vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(1));
     /// ```
-    pub fn make_proposal(&mut self, description: String, suggested_changes: Vec<Change>, current_epoch: u64, total_emitted_tokens: Decimal)
+    pub fn make_proposal(&mut self, metadata: ProposalMetadata, suggested_changes: Vec<Change>, current_epoch: u64, reference_supply: Decimal)
     {
+       metadata.check();
+       let first_version = ProposalVersion { metadata: metadata.clone(), changes: suggested_changes.clone(), epoch: current_epoch };
        let proposal = Proposal
        {
            id: self.new_proposal_id,
-           description,
+           metadata,
            changes: suggested_changes,
            status: ProposalStatus::SuggestionPhase,
            supporting_votes: Decimal::zero(),
            voted_for: Decimal::zero(),
            voted_against: Decimal::zero(),
+           raw_votes: Decimal::zero(),
            blank_votes: Decimal::zero(),
            delegated_votes: HashMap::new(),
            delegation_to: HashMap::new(),
            epoch_expiration: current_epoch + self.support_period,
-           total_emitted_tokens: total_emitted_tokens
+           reference_supply: reference_supply,
+           vetoed_by: vec![],
+           objections: Decimal::zero(),
+           objected_by: vec![],
+           fast_track: false,
+           multiple_choice: None,
+           prerequisites: vec![],
+           author: None,
+           supporters: vec![],
+           versions: vec![first_version],
+           amendments: vec![]
        };
 
         self.new_proposal_id += 1;
@@ -115,8 +359,9 @@ impl BallotBox
     /// ```
     /// use radix_engine::ledger::TypedInMemorySubstateStore;
     /// use styx::ballot_box::BallotBox;
-    /// use styx::proposal::Change;
+    /// use styx::proposal::{Change, ProposalMetadata};
     /// use scrypto::prelude::dec;
+    /// use scrypto::crypto::Hash;
     /// use scrypto_unit::TestRunner;
     /// use styx::voter_card::VoterCard;
     ///
@@ -125,7 +370,9 @@ impl BallotBox
     /// let mut test_runner = TestRunner::new(true, &mut store);
     /// let mut voter_card = VoterCard::new(0);
     ///
-    /// ballet_box.make_proposal(String::from("An example Proposal"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(1));
+    /// let metadata = ProposalMetadata { title: String::from("Example"), summary: String::from("An example Proposal"),
+    ///     discussion_url: String::from("https://forum.example.com/proposal"), content_hash: Hash([0; 32]) };
+    /// ballet_box.make_proposal(metadata, vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(1));
     /// ballet_box.support_proposal(0, &mut voter_card, test_runner.get_current_epoch());
     /// ```
     pub fn support_proposal(&mut self, proposal_id: usize, voter_card: &mut VoterCard, current_epoch: u64)
@@ -136,13 +383,16 @@ impl BallotBox
         assert!(proposal.epoch_expiration > current_epoch, "This proposal has expired");
         assert!(proposal.status.is_suggestion_phase(), "Cannot support a proposal that is not in suggestion phase");
 
-        let can_support = voter_card.try_vote_for(proposal_id, &proposal.status);
-
-        if !can_support
+        // The support of a voter is recorded on its VoterCard only once, even if the support of the
+        // proposal is reset by an amendment
+        if proposal.supporters.contains(&voter_card.voter_id)
         {
             panic!("You already supported the proposition");
         }
+        voter_card.try_vote_for(proposal_id, &proposal.status);
+
         let voting_power = voter_card.voting_power(current_epoch);
+        proposal.supporters.push(voter_card.voter_id);
         proposal.supporting_votes = proposal.supporting_votes + voting_power;
     }
 
@@ -156,8 +406,9 @@ impl BallotBox
     /// ```
     /// use radix_engine::ledger::TypedInMemorySubstateStore;
     /// use styx::ballot_box::BallotBox;
-    /// use styx::proposal::Change;
+    /// use styx::proposal::{Change, ProposalMetadata};
     /// use scrypto::prelude::dec;
+    /// use scrypto::crypto::Hash;
     /// use scrypto_unit::TestRunner;
     /// use styx::voter_card::VoterCard;
     ///
@@ -166,7 +417,9 @@ impl BallotBox
     /// let mut test_runner = TestRunner::new(true, &mut store);
     /// let mut voter_card = VoterCard::new(0);
     ///
-    /// ballet_box.make_proposal(String::from("An example Proposal"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(1));
+    /// let metadata = ProposalMetadata { title: String::from("Example"), summary: String::from("An example Proposal"),
+    ///     discussion_url: String::from("https://forum.example.com/proposal"), content_hash: Hash([0; 32]) };
+    /// ballet_box.make_proposal(metadata, vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(1));
     /// // Make the time forward so that the proposal can advance to voting phase
     /// let current_epoch = test_runner.get_current_epoch();
     /// test_runner.set_current_epoch(current_epoch + 168 +1 );
@@ -175,18 +428,44 @@ impl BallotBox
     /// ```
     pub fn advance_with_proposal(&mut self, proposal_id: usize, current_epoch: u64)
         -> Option<Vec<Change>>
+    {
+        match self.advance_proposal(proposal_id, current_epoch)
+        {
+            None => None,
+            Some(changes) => self.execute_proposal(&changes)
+        }
+    }
+
+    /// Makes a proposal advance to its next phase if possible without executing it. If the proposal
+    /// is accepted, its changes are returned and should either be executed or its execution marked
+    /// as failed.
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal to try to make advance
+    /// * `current_epoch` - current epoch
+    pub fn advance_proposal(&mut self, proposal_id: usize, current_epoch: u64)
+        -> Option<Vec<Change>>
     {
         assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
 
+        let prerequisite_failed = self.prerequisite_failed(proposal_id);
+        let prerequisites_executed = self.prerequisites_executed(proposal_id);
         let proposal: &mut Proposal = self.proposals.get_mut(proposal_id).unwrap();
         assert!(proposal.epoch_expiration <= current_epoch, "This proposal has not finished its current period");
 
+        // A proposal can never be executed if one of its prerequisites failed
+        let is_ongoing = !proposal.status.is_failed() && !proposal.status.is_proposal_accepted();
+        if is_ongoing && prerequisite_failed
+        {
+            proposal.status = ProposalStatus::ProposalRejected;
+            return None;
+        }
 
         match proposal.status
         {
             ProposalStatus::SuggestionPhase =>
                 {
-                    if proposal.supporting_votes / proposal.total_emitted_tokens >= self.suggestion_approval_threshold
+                    if proposal.supporting_votes / proposal.reference_supply >= self.suggestion_approval_threshold
                     {
                         proposal.status = ProposalStatus::VotingPhase;
                         proposal.epoch_expiration = current_epoch + self.vote_period;
@@ -198,6 +477,42 @@ impl BallotBox
                     None
                 }
 
+            ProposalStatus::VotingPhase if proposal.multiple_choice.is_some() =>
+                {
+                    let multiple_choice = proposal.multiple_choice.as_ref().unwrap();
+                    let winner = if multiple_choice.total_votes >= self.minimum_votes_threshold { multiple_choice.winner() } else { None };
+
+                    match winner
+                    {
+                        None =>
+                            {
+                                proposal.status = ProposalStatus::ProposalRejected;
+                                None
+                            }
+                        Some(option) =>
+                            {
+                                proposal.changes = multiple_choice.options[option].changes.clone();
+                                self.accept_proposal(proposal_id, current_epoch)
+                            }
+                    }
+                }
+
+            ProposalStatus::VotingPhase if proposal.fast_track =>
+                {
+                    let total_votes = proposal.voted_for + proposal.voted_against;
+                    // The quorum is computed from the voting power before the power control function
+                    let quorum_reached = proposal.raw_votes / proposal.reference_supply >= self.fast_track_quorum;
+                    if quorum_reached && proposal.voted_for >= total_votes * self.fast_track_supermajority
+                    {
+                        self.accept_proposal(proposal_id, current_epoch)
+                    }
+                    else
+                    {
+                        proposal.status = ProposalStatus::ProposalRejected;
+                        None
+                    }
+                }
+
             ProposalStatus::VotingPhase =>
                 {
 
@@ -205,9 +520,7 @@ impl BallotBox
                     {
                         if proposal.voted_for >= proposal.voted_against
                         {
-                            proposal.status = ProposalStatus::ProposalAccepted;
-                            let changes = proposal.changes.clone();
-                            self.execute_proposal(&changes)
+                            self.accept_proposal(proposal_id, current_epoch)
                         }
                         else
                         {
@@ -221,130 +534,172 @@ impl BallotBox
                         None
                     }
                 }
+            ProposalStatus::VetoPhase =>
+                {
+                    assert!(prerequisites_executed, "The prerequisites of this proposal have not been executed yet");
+                    proposal.status = ProposalStatus::ProposalAccepted;
+                    Some(proposal.changes.clone())
+                }
+            ProposalStatus::ChallengePhase =>
+                {
+                    // Optimistic proposals with enough objections are already in voting phase
+                    self.accept_proposal(proposal_id, current_epoch)
+                }
             _ => { panic!("Proposal cannot advance forward! It has already been accepted or rejected.") }
         }
     }
 
-    /// Enables a voter to delegate its token to another voter for a given proposal
+    /// Marks the execution of an accepted proposal as failed. None of its changes are made and the
+    /// proposals depending on it are rejected when they advance.
     ///
     /// # Arguments
-    /// * `proposal_id` - id of the Proposal to for which to delegate its tokens
-    /// * `delegate_to` - VoterCard's id of the user to delegate its tokens to
-    /// * `voter_card` - VoterCard of the user delegating
-    /// * `current_epoch` - current epoch
-    ///
-    /// # Examples
-    /// ```
-    /// use radix_engine::ledger::TypedInMemorySubstateStore;
-    /// use styx::ballot_box::BallotBox;
-    /// use styx::proposal::Change;
-    /// use scrypto::prelude::dec;
-    /// use scrypto_unit::TestRunner;
-    /// use styx::voter_card::VoterCard;
-    ///
-    ///let mut ballet_box = BallotBox::new();
-    ///let mut store = TypedInMemorySubstateStore::with_bootstrap();
-    ///let mut test_runner = TestRunner::new(true, &mut store);
-    ///let mut voter_card = VoterCard::new(0);
-    /// // Add voter id as approved
-    ///voter_card.approve(1, test_runner.get_current_epoch());
-    ///voter_card.add_tokens(dec!(100), test_runner.get_current_epoch());
-    ///let current_epoch = test_runner.get_current_epoch();
-    /// // Advance time to make the tokens worth something
-    ///test_runner.set_current_epoch(current_epoch + 2016 );
-    ///
-    /// ballet_box.make_proposal(String::from("An example Proposal"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(100));
-    /// ballet_box.support_proposal(0, &mut voter_card, test_runner.get_current_epoch());
-    /// // Advance time to end of vote
-    /// let current_epoch = test_runner.get_current_epoch();
-    /// test_runner.set_current_epoch(current_epoch + 170 );
-    /// // Now the proposal can advance forward
-    /// ballet_box.advance_with_proposal(0, test_runner.get_current_epoch());
-    /// ballet_box.delegate_for_proposal(0, 1, &mut voter_card, test_runner.get_current_epoch());
-    /// ```
-    pub fn delegate_for_proposal(&mut self, proposal_id: usize, delegate_to: u64, voter_card: &mut VoterCard, current_epoch: u64)
+    /// * `proposal_id` - id of the Proposal
+    pub fn fail_execution(&mut self, proposal_id: usize)
     {
         assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
-        assert_ne!(delegate_to, voter_card.voter_id, "Delegating to yourself does not make sense");
-        assert!(voter_card.approves(delegate_to), "Cannot delegate to this person id");
 
         let proposal: &mut Proposal = self.proposals.get_mut(proposal_id).unwrap();
-        assert!(proposal.epoch_expiration > current_epoch, "The voting period has already ended for this proposal.");
-        assert!(proposal.status.is_voting_phase(), "The proposal is not in its voting phase.");
+        assert!(proposal.status.is_proposal_accepted(), "Only the execution of an accepted proposal can fail");
+        proposal.status = ProposalStatus::ExecutionFailed;
+    }
 
-        let can_delegate = voter_card.try_vote_for(proposal_id, &proposal.status);
+    /// Marks as failed a proposal that could be accepted but whose execution keeps reverting, once
+    /// the execution grace period has passed since the end of its last period. The proposals
+    /// depending on it are rejected when they advance.
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal
+    /// * `current_epoch` - current epoch
+    pub fn fail_stuck_proposal(&mut self, proposal_id: usize, current_epoch: u64)
+    {
+        assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
 
-        if !can_delegate
-        {
-            panic!("Already voted or delegated for this proposal!");
-        }
-        else
+        let executable_since = self.proposals.get(proposal_id).unwrap().epoch_expiration;
+        assert!(executable_since + self.execution_grace_period <= current_epoch, "The execution grace period of this proposal has not ended");
+
+        match self.advance_proposal(proposal_id, current_epoch)
         {
-            let nb_votes = voter_card.voting_power(current_epoch);
-            proposal.add_delegation(voter_card.voter_id, delegate_to, nb_votes);
+            None => { panic!("This proposal has no changes to execute"); }
+            Some(_) => self.fail_execution(proposal_id)
         }
+    }
 
+    /// Sets the proposals that should be executed before a proposal can be executed. The proposal
+    /// is rejected when it advances if one of its prerequisites failed.
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal
+    /// * `prerequisites` - ids of earlier proposals
+    pub fn set_prerequisites(&mut self, proposal_id: usize, prerequisites: Vec<usize>)
+    {
+        assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
+        assert!(prerequisites.iter().all(|id| *id < proposal_id), "A prerequisite should be an earlier proposal");
 
+        self.proposals[proposal_id].prerequisites = prerequisites;
+    }
 
+    /// Creates a new optimistic proposal. It can only spend amounts under the optimistic limits
+    /// and is accepted at the end of the challenge period unless enough voting power objected to it
+    ///
+    /// # Arguments
+    /// * `metadata` - title, summary, discussion URL and content hash of the Proposal
+    /// * `suggested_changes` - changes that will be made to the DAO with this proposal
+    /// * `current_epoch` - current epoch
+    /// * `reference_supply` - reference supply of DAO tokens
+    pub fn make_optimistic_proposal(&mut self, metadata: ProposalMetadata, suggested_changes: Vec<Change>, current_epoch: u64, reference_supply: Decimal)
+    {
+        assert!(self.is_optimistic_eligible(&suggested_changes), "These changes cannot be made by an optimistic proposal");
 
+        self.make_proposal(metadata, suggested_changes, current_epoch, reference_supply);
+        let proposal = self.proposals.last_mut().unwrap();
+        proposal.status = ProposalStatus::ChallengePhase;
+        proposal.epoch_expiration = current_epoch + self.challenge_period;
     }
 
-    /// Enables a voter to vote for a specific proposal using its own tokens and the tokens of people
-    /// who delegated to them.
+    /// Creates a new fast-track proposal. It skips the suggestion phase and has a shorter voting
+    /// phase but needs a higher quorum and a supermajority to be accepted. Only some changes can
+    /// be made by fast-track proposals.
     ///
     /// # Arguments
-    /// * `proposal_id` - id of the Proposal for which to vote
-    /// * `voter_card` - VoterCard of the user delegating
-    /// * `vote` - Vote to cast
+    /// * `metadata` - title, summary, discussion URL and content hash of the Proposal
+    /// * `suggested_changes` - changes that will be made to the DAO with this proposal
     /// * `current_epoch` - current epoch
-    ///
-    /// # Examples
-    /// ```
-    /// use radix_engine::ledger::TypedInMemorySubstateStore;
-    /// use styx::ballot_box::BallotBox;
-    /// use styx::proposal::{Change, Vote};
-    /// use scrypto::prelude::dec;
-    /// use scrypto_unit::TestRunner;
-    /// use styx::voter_card::VoterCard;
-    ///
-    /// let mut ballet_box = BallotBox::new();
-    /// let mut store = TypedInMemorySubstateStore::with_bootstrap();
-    /// let mut test_runner = TestRunner::new(true, &mut store);
-    /// let mut voter_card = VoterCard::new(0);
-    /// // Add voter id as approved
-    /// voter_card.approve(1, test_runner.get_current_epoch());
-    /// voter_card.add_tokens(dec!(100), test_runner.get_current_epoch());
-    /// let current_epoch = test_runner.get_current_epoch();
-    /// // Advance time to make the tokens worth something
-    /// test_runner.set_current_epoch(current_epoch + 2016 );
-    ///
-    /// ballet_box.make_proposal(String::from("An example Proposal"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(100));
-    /// ballet_box.support_proposal(0, &mut voter_card, test_runner.get_current_epoch());
-    /// // Advance time to end of vote
-    /// let current_epoch = test_runner.get_current_epoch();
-    /// test_runner.set_current_epoch(current_epoch + 170 );
-    /// // Now the proposal can advance forward
-    /// ballet_box.advance_with_proposal(0, test_runner.get_current_epoch());
-    /// ballet_box.vote_for_proposal(0,&mut voter_card, Vote::For, test_runner.get_current_epoch());
-    /// ```
-    pub fn vote_for_proposal(&mut self, proposal_id: usize, voter_card: &mut VoterCard, vote: Vote, current_epoch: u64)
+    /// * `reference_supply` - reference supply of DAO tokens
+    pub fn make_fast_track_proposal(&mut self, metadata: ProposalMetadata, suggested_changes: Vec<Change>, current_epoch: u64, reference_supply: Decimal)
     {
-        assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
+        assert!(!suggested_changes.is_empty() && suggested_changes.iter().all(|change| change.is_fast_track_eligible()),
+                "These changes cannot be made by a fast-track proposal");
 
-        let proposal: &mut Proposal = self.proposals.get_mut(proposal_id).unwrap();
-        assert!(proposal.epoch_expiration > current_epoch, "The voting period has already ended for this proposal.");
-        assert!(proposal.status.is_voting_phase(), "The proposal is not in its voting phase.");
+        self.make_proposal(metadata, suggested_changes, current_epoch, reference_supply);
+        let proposal = self.proposals.last_mut().unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
+        proposal.epoch_expiration = current_epoch + self.fast_track_vote_period;
+        proposal.fast_track = true;
+    }
+
+    /// Creates a new multiple-choice proposal. It goes through the same phases as other proposals
+    /// but only the changes of the winning option are executed
+    ///
+    /// # Arguments
+    /// * `metadata` - title, summary, discussion URL and content hash of the Proposal
+    /// * `options` - options to choose from, with their changes
+    /// * `mode` - voting mode of the proposal
+    /// * `current_epoch` - current epoch
+    /// * `reference_supply` - reference supply of DAO tokens
+    pub fn make_multiple_choice_proposal(&mut self, metadata: ProposalMetadata, options: Vec<ProposalOption>, mode: VotingMode, current_epoch: u64, reference_supply: Decimal)
+    {
+        let multiple_choice = MultipleChoice::new(mode, options);
 
+        self.make_proposal(metadata, vec![], current_epoch, reference_supply);
+        self.proposals.last_mut().unwrap().multiple_choice = Some(multiple_choice);
+    }
 
-        let mut total_voting_power = dec!(0);
-        let can_vote = voter_card.try_vote_for(proposal_id, &proposal.status);
+    /// Creates a new non-binding poll that can directly be voted on until the end of the poll period
+    ///
+    /// # Arguments
+    /// * `description` - question asked by the Poll
+    /// * `options` - options to choose from
+    /// * `current_epoch` - current epoch
+    /// * `reference_supply` - reference supply of DAO tokens
+    pub fn make_poll(&mut self, description: String, options: Vec<String>, current_epoch: u64, reference_supply: Decimal)
+    {
+        assert!(options.len() >= 2, "A poll needs at least two options");
 
-        if can_vote
+        let poll = Poll
         {
-            total_voting_power = voter_card.voting_power(current_epoch);
-        }
+            id: self.polls.len(),
+            description,
+            tallies: vec![Decimal::zero(); options.len()],
+            options,
+            voters: vec![],
+            delegated_votes: HashMap::new(),
+            delegation_to: HashMap::new(),
+            epoch_expiration: current_epoch + self.poll_period,
+            reference_supply
+        };
+        self.polls.push(poll);
+    }
 
-        match proposal.delegated_votes.get_mut(&voter_card.voter_id)
+    /// Votes for an option of a poll with the voting power of a VoterCard and the votes delegated
+    /// to it. Unlike votes for proposals, it does not change the VoterCard
+    ///
+    /// # Arguments
+    /// * `poll_id` - id of the Poll
+    /// * `voter_card` - VoterCard of the user voting
+    /// * `option` - index of the chosen option
+    /// * `current_epoch` - current epoch
+    pub fn vote_for_poll(&mut self, poll_id: usize, voter_card: &VoterCard, option: usize, current_epoch: u64)
+    {
+        assert!(poll_id < self.polls.len(), "This poll does not exist!");
+
+        let poll: &mut Poll = self.polls.get_mut(poll_id).unwrap();
+        assert!(poll.is_open(current_epoch), "This poll has ended");
+        assert!(option < poll.options.len(), "This option does not exist");
+        assert!(!poll.voters.contains(&voter_card.voter_id), "Already voted or delegated for this poll!");
+
+        poll.voters.push(voter_card.voter_id);
+        let mut total_voting_power = voter_card.voting_power(current_epoch);
+        match poll.delegated_votes.get_mut(&voter_card.voter_id)
         {
             None => {}
             Some(deleg_votes) =>
@@ -354,30 +709,331 @@ impl BallotBox
                 }
         }
 
-        let true_amount_of_votes = Self::power_control_function(total_voting_power, proposal.total_emitted_tokens);
-        match vote
-        {
-            Vote::For => { proposal.voted_for = proposal.voted_for + true_amount_of_votes; }
-            Vote::Against => { proposal.voted_against = proposal.voted_against + true_amount_of_votes; }
-            Vote::Blank => { proposal.blank_votes = proposal.blank_votes + true_amount_of_votes; }
-        }
+        let true_amount_of_votes = Self::power_control_function(total_voting_power, poll.reference_supply);
+        poll.tallies[option] = poll.tallies[option] + true_amount_of_votes;
     }
 
-    /// Internal function that executes a given list of changes and returns
+    /// Enables a voter to delegate its voting power to another voter for a given poll
     ///
     /// # Arguments
-    /// * `proposal_id` - id of the Proposal for which to vote
+    /// * `poll_id` - id of the Poll
+    /// * `delegate_to` - VoterCard's id of the user to delegate its voting power to
     /// * `voter_card` - VoterCard of the user delegating
-    /// * `vote` - Vote to cast
     /// * `current_epoch` - current epoch
-    fn execute_proposal(&mut self, changes_to_do: &Vec<Change>) -> Option<Vec<Change>>
+    pub fn delegate_for_poll(&mut self, poll_id: usize, delegate_to: u64, voter_card: &VoterCard, current_epoch: u64)
     {
-        let mut changes_to_return = vec![];
-        for change in changes_to_do
-        {
-            match change
-            {
-                Change::ChangeSupportPeriod(new_period) =>
+        assert!(poll_id < self.polls.len(), "This poll does not exist!");
+        assert_ne!(delegate_to, voter_card.voter_id, "Delegating to yourself does not make sense");
+        assert!(voter_card.approves(delegate_to), "Cannot delegate to this person id");
+
+        let poll: &mut Poll = self.polls.get_mut(poll_id).unwrap();
+        assert!(poll.is_open(current_epoch), "This poll has ended");
+        assert!(!poll.voters.contains(&voter_card.voter_id), "Already voted or delegated for this poll!");
+
+        poll.voters.push(voter_card.voter_id);
+        add_delegation(&mut poll.delegation_to, &mut poll.delegated_votes, voter_card.voter_id, delegate_to, voter_card.voting_power(current_epoch));
+    }
+
+    /// Objects to an optimistic proposal during its challenge phase. If the objections reach the
+    /// objection threshold, the proposal goes to voting phase
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal to object to
+    /// * `voter_card` - VoterCard of the user objecting
+    /// * `current_epoch` - current epoch
+    pub fn object_to_proposal(&mut self, proposal_id: usize, voter_card: &VoterCard, current_epoch: u64)
+    {
+        assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
+
+        let proposal: &mut Proposal = self.proposals.get_mut(proposal_id).unwrap();
+        assert!(proposal.status.is_challenge_phase(), "This proposal is not in challenge phase");
+        assert!(proposal.epoch_expiration > current_epoch, "The challenge period of this proposal has ended");
+        assert!(!proposal.objected_by.contains(&voter_card.voter_id), "You already objected to this proposal");
+
+        proposal.objected_by.push(voter_card.voter_id);
+        proposal.objections = proposal.objections + voter_card.voting_power(current_epoch);
+
+        if proposal.objections / proposal.reference_supply >= self.objection_threshold
+        {
+            proposal.status = ProposalStatus::VotingPhase;
+            proposal.epoch_expiration = current_epoch + self.vote_period;
+        }
+    }
+
+    /// Internal function that accepts a proposal. The proposal enters its veto phase if there is a
+    /// veto council, otherwise its changes are returned to be executed
+    fn accept_proposal(&mut self, proposal_id: usize, current_epoch: u64) -> Option<Vec<Change>>
+    {
+        let prerequisites_executed = self.prerequisites_executed(proposal_id);
+        let proposal: &mut Proposal = self.proposals.get_mut(proposal_id).unwrap();
+
+        // Proposals electing a new council cannot be vetoed so that the council cannot block its
+        // own replacement
+        let can_be_vetoed = !self.veto_council.is_empty()
+            && !proposal.changes.iter().any(|change| matches!(change, Change::ElectVetoCouncil(_)));
+
+        if can_be_vetoed
+        {
+            proposal.status = ProposalStatus::VetoPhase;
+            proposal.epoch_expiration = current_epoch + self.veto_period;
+            None
+        }
+        else
+        {
+            assert!(prerequisites_executed, "The prerequisites of this proposal have not been executed yet");
+            proposal.status = ProposalStatus::ProposalAccepted;
+            Some(proposal.changes.clone())
+        }
+    }
+
+    /// Internal function that returns a proposal that can be amended: a proposal in suggestion
+    /// phase which is not a multiple-choice proposal
+    fn get_amendable_proposal(&mut self, proposal_id: usize, current_epoch: u64) -> &mut Proposal
+    {
+        assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
+
+        let proposal: &mut Proposal = self.proposals.get_mut(proposal_id).unwrap();
+        assert!(proposal.status.is_suggestion_phase(), "Only a proposal in suggestion phase can be amended");
+        assert!(proposal.epoch_expiration > current_epoch, "This proposal has expired");
+        assert!(proposal.multiple_choice.is_none(), "A multiple-choice proposal cannot be amended");
+        proposal
+    }
+
+    /// Internal function that returns whether one of the prerequisites of a proposal failed
+    fn prerequisite_failed(&self, proposal_id: usize) -> bool
+    {
+        self.proposals[proposal_id].prerequisites.iter().any(|id| self.proposals[*id].status.is_failed())
+    }
+
+    /// Internal function that returns whether all the prerequisites of a proposal were executed
+    fn prerequisites_executed(&self, proposal_id: usize) -> bool
+    {
+        self.proposals[proposal_id].prerequisites.iter().all(|id| self.proposals[*id].status.is_proposal_accepted())
+    }
+
+    /// Internal function that returns whether the given changes can be made by an optimistic
+    /// proposal: they should only spend resources and stay under the optimistic limits
+    fn is_optimistic_eligible(&self, changes: &Vec<Change>) -> bool
+    {
+        let mut spent: HashMap<ResourceAddress, Decimal> = HashMap::new();
+        for change in changes
+        {
+            let (address, amount) = match change
+            {
+                Change::AllowSpending(address, amount, _) => (address, amount),
+                Change::PayAccount(address, amount, _) => (address, amount),
+                _ => { return false; }
+            };
+
+            let total = spent.entry(*address).or_insert(Decimal::zero());
+            *total = *total + *amount;
+        }
+
+        !changes.is_empty() && spent.iter().all(|(address, total)| {
+            match self.optimistic_limits.get(address)
+            {
+                None => false,
+                Some(limit) => *total <= *limit
+            }
+        })
+    }
+
+    /// Enables a member of the veto council to veto a proposal during its veto phase. The proposal
+    /// is vetoed once a majority of the council vetoed it.
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal to veto
+    /// * `voter_id` - VoterCard id of the council member vetoing
+    /// * `current_epoch` - current epoch
+    pub fn veto_proposal(&mut self, proposal_id: usize, voter_id: u64, current_epoch: u64)
+    {
+        assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
+        assert!(self.veto_council.contains(&voter_id), "Only members of the veto council can veto a proposal");
+
+        let proposal: &mut Proposal = self.proposals.get_mut(proposal_id).unwrap();
+        assert!(proposal.status.is_veto_phase(), "This proposal is not in veto phase");
+        assert!(proposal.epoch_expiration > current_epoch, "The veto period of this proposal has ended");
+        assert!(!proposal.vetoed_by.contains(&voter_id), "You already vetoed this proposal");
+
+        proposal.vetoed_by.push(voter_id);
+        self.veto_log.push(VetoAction { proposal_id, voter_id, epoch: current_epoch });
+
+        let council = &self.veto_council;
+        let council_vetoes = proposal.vetoed_by.iter().filter(|id| council.contains(*id)).count();
+        if 2 * council_vetoes > council.len()
+        {
+            proposal.status = ProposalStatus::Vetoed;
+        }
+    }
+
+    /// Enables a voter to delegate its token to another voter for a given proposal
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal to for which to delegate its tokens
+    /// * `delegate_to` - VoterCard's id of the user to delegate its tokens to
+    /// * `voter_card` - VoterCard of the user delegating
+    /// * `current_epoch` - current epoch
+    ///
+    /// # Examples
+    /// ```
+    /// use radix_engine::ledger::TypedInMemorySubstateStore;
+    /// use styx::ballot_box::BallotBox;
+    /// use styx::proposal::{Change, ProposalMetadata};
+    /// use scrypto::prelude::dec;
+    /// use scrypto::crypto::Hash;
+    /// use scrypto_unit::TestRunner;
+    /// use styx::voter_card::VoterCard;
+    ///
+    ///let mut ballet_box = BallotBox::new();
+    ///let mut store = TypedInMemorySubstateStore::with_bootstrap();
+    ///let mut test_runner = TestRunner::new(true, &mut store);
+    ///let mut voter_card = VoterCard::new(0);
+    /// // Add voter id as approved
+    ///voter_card.approve(1, test_runner.get_current_epoch());
+    ///voter_card.add_tokens(dec!(100), test_runner.get_current_epoch());
+    ///let current_epoch = test_runner.get_current_epoch();
+    /// // Advance time to make the tokens worth something
+    ///test_runner.set_current_epoch(current_epoch + 2016 );
+    ///
+    /// let metadata = ProposalMetadata { title: String::from("Example"), summary: String::from("An example Proposal"),
+    ///     discussion_url: String::from("https://forum.example.com/proposal"), content_hash: Hash([0; 32]) };
+    /// ballet_box.make_proposal(metadata, vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(100));
+    /// ballet_box.support_proposal(0, &mut voter_card, test_runner.get_current_epoch());
+    /// // Advance time to end of vote
+    /// let current_epoch = test_runner.get_current_epoch();
+    /// test_runner.set_current_epoch(current_epoch + 170 );
+    /// // Now the proposal can advance forward
+    /// ballet_box.advance_with_proposal(0, test_runner.get_current_epoch());
+    /// ballet_box.delegate_for_proposal(0, 1, &mut voter_card, test_runner.get_current_epoch());
+    /// ```
+    pub fn delegate_for_proposal(&mut self, proposal_id: usize, delegate_to: u64, voter_card: &mut VoterCard, current_epoch: u64)
+    {
+        assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
+        assert_ne!(delegate_to, voter_card.voter_id, "Delegating to yourself does not make sense");
+        assert!(voter_card.approves(delegate_to), "Cannot delegate to this person id");
+
+        let proposal: &mut Proposal = self.proposals.get_mut(proposal_id).unwrap();
+        assert!(proposal.epoch_expiration > current_epoch, "The voting period has already ended for this proposal.");
+        assert!(proposal.status.is_voting_phase(), "The proposal is not in its voting phase.");
+
+        let can_delegate = voter_card.try_vote_for(proposal_id, &proposal.status);
+
+        if !can_delegate
+        {
+            panic!("Already voted or delegated for this proposal!");
+        }
+        else
+        {
+            let nb_votes = voter_card.voting_power(current_epoch);
+            proposal.add_delegation(voter_card.voter_id, delegate_to, nb_votes);
+        }
+
+
+
+
+    }
+
+    /// Enables a voter to vote for a specific proposal using its own tokens and the tokens of people
+    /// who delegated to them.
+    ///
+    /// # Arguments
+    /// * `proposal_id` - id of the Proposal for which to vote
+    /// * `voter_card` - VoterCard of the user delegating
+    /// * `vote` - Vote to cast
+    /// * `current_epoch` - current epoch
+    ///
+    /// # Examples
+    /// ```
+    /// use radix_engine::ledger::TypedInMemorySubstateStore;
+    /// use styx::ballot_box::BallotBox;
+    /// use styx::proposal::{Change, ProposalMetadata, Vote};
+    /// use scrypto::prelude::dec;
+    /// use scrypto::crypto::Hash;
+    /// use scrypto_unit::TestRunner;
+    /// use styx::voter_card::VoterCard;
+    ///
+    /// let mut ballet_box = BallotBox::new();
+    /// let mut store = TypedInMemorySubstateStore::with_bootstrap();
+    /// let mut test_runner = TestRunner::new(true, &mut store);
+    /// let mut voter_card = VoterCard::new(0);
+    /// // Add voter id as approved
+    /// voter_card.approve(1, test_runner.get_current_epoch());
+    /// voter_card.add_tokens(dec!(100), test_runner.get_current_epoch());
+    /// let current_epoch = test_runner.get_current_epoch();
+    /// // Advance time to make the tokens worth something
+    /// test_runner.set_current_epoch(current_epoch + 2016 );
+    ///
+    /// let metadata = ProposalMetadata { title: String::from("Example"), summary: String::from("An example Proposal"),
+    ///     discussion_url: String::from("https://forum.example.com/proposal"), content_hash: Hash([0; 32]) };
+    /// ballet_box.make_proposal(metadata, vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(100));
+    /// ballet_box.support_proposal(0, &mut voter_card, test_runner.get_current_epoch());
+    /// // Advance time to end of vote
+    /// let current_epoch = test_runner.get_current_epoch();
+    /// test_runner.set_current_epoch(current_epoch + 170 );
+    /// // Now the proposal can advance forward
+    /// ballet_box.advance_with_proposal(0, test_runner.get_current_epoch());
+    /// ballet_box.vote_for_proposal(0,&mut voter_card, Vote::For, test_runner.get_current_epoch());
+    /// ```
+    pub fn vote_for_proposal(&mut self, proposal_id: usize, voter_card: &mut VoterCard, vote: Vote, current_epoch: u64)
+    {
+        assert!(proposal_id < self.new_proposal_id, "This proposal does not exist!");
+
+        let proposal: &mut Proposal = self.proposals.get_mut(proposal_id).unwrap();
+        assert!(proposal.epoch_expiration > current_epoch, "The voting period has already ended for this proposal.");
+        assert!(proposal.status.is_voting_phase(), "The proposal is not in its voting phase.");
+
+
+        let mut total_voting_power = dec!(0);
+        let can_vote = voter_card.try_vote_for(proposal_id, &proposal.status);
+
+        if can_vote
+        {
+            total_voting_power = voter_card.voting_power(current_epoch);
+        }
+
+        match proposal.delegated_votes.get_mut(&voter_card.voter_id)
+        {
+            None => {}
+            Some(deleg_votes) =>
+                {
+                    total_voting_power = total_voting_power + *deleg_votes;
+                    *deleg_votes = dec!(0);
+                }
+        }
+
+        let true_amount_of_votes = Self::power_control_function(total_voting_power, proposal.reference_supply);
+        match (vote, &mut proposal.multiple_choice)
+        {
+            (Vote::Options(choices), Some(multiple_choice)) => { multiple_choice.cast(choices, true_amount_of_votes); }
+            (Vote::Options(_), None) => { panic!("This proposal is not a multiple-choice proposal"); }
+            (_, Some(_)) => { panic!("A multiple-choice proposal should be voted with options"); }
+            (Vote::For, None) =>
+                {
+                    proposal.voted_for = proposal.voted_for + true_amount_of_votes;
+                    proposal.raw_votes = proposal.raw_votes + total_voting_power;
+                }
+            (Vote::Against, None) =>
+                {
+                    proposal.voted_against = proposal.voted_against + true_amount_of_votes;
+                    proposal.raw_votes = proposal.raw_votes + total_voting_power;
+                }
+            (Vote::Blank, None) => { proposal.blank_votes = proposal.blank_votes + true_amount_of_votes; }
+        }
+    }
+
+    /// Executes the changes of an accepted proposal that are made to the BallotBox and returns the
+    /// ones that should be made by the Styx blueprint
+    ///
+    /// # Arguments
+    /// * `changes_to_do` - changes of the accepted Proposal
+    pub fn execute_proposal(&mut self, changes_to_do: &Vec<Change>) -> Option<Vec<Change>>
+    {
+        let mut changes_to_return = vec![];
+        for change in changes_to_do
+        {
+            match change
+            {
+                Change::ChangeSupportPeriod(new_period) =>
                     {
                         self.support_period = *new_period;
                     }
@@ -393,6 +1049,56 @@ impl BallotBox
                     {
                         self.minimum_votes_threshold = *threshold;
                     }
+                Change::ChangeSupplyReference(supply_reference) =>
+                    {
+                        self.supply_reference = supply_reference.clone();
+                    }
+                Change::ElectVetoCouncil(council) =>
+                    {
+                        self.veto_council = council.clone();
+                    }
+                Change::ChangeVetoPeriod(period) =>
+                    {
+                        self.veto_period = *period;
+                    }
+                Change::ChangeOptimisticLimit(address, limit) =>
+                    {
+                        match limit
+                        {
+                            None => { self.optimistic_limits.remove(address); }
+                            Some(limit) => { self.optimistic_limits.insert(*address, *limit); }
+                        }
+                    }
+                Change::ChangeChallengePeriod(period) =>
+                    {
+                        self.challenge_period = *period;
+                    }
+                Change::ChangeObjectionThreshold(threshold) =>
+                    {
+                        self.objection_threshold = *threshold;
+                    }
+                Change::ChangePollPeriod(period) =>
+                    {
+                        self.poll_period = *period;
+                    }
+                Change::ChangeAmendmentRule(rule) =>
+                    {
+                        self.amendment_rule = rule.clone();
+                    }
+                Change::ChangeAmendmentThreshold(threshold) =>
+                    {
+                        self.amendment_support_threshold = *threshold;
+                    }
+                Change::ChangeExecutionGracePeriod(period) =>
+                    {
+                        self.execution_grace_period = *period;
+                    }
+                Change::ChangeFastTrackRules(vote_period, quorum, supermajority) =>
+                    {
+                        self.fast_track_vote_period = *vote_period;
+                        self.fast_track_quorum = *quorum;
+                        self.fast_track_supermajority = *supermajority;
+                    }
                 Change::AllowSpending(address, amount, to) =>
                     {
                         changes_to_return.push(Change::AllowSpending(address.clone(), amount.clone(), *to));
@@ -401,533 +1107,1253 @@ impl BallotBox
                     {
                         changes_to_return.push(Change::AllowMinting(amount.clone()));
                     }
-            }
-        }
-
-        if changes_to_return.is_empty()
-        {
-            None
-        }
-        else
-        {
-            Some(changes_to_return)
-        }
+                Change::SuspendVoter(voter_id, until_epoch) =>
+                    {
+                        changes_to_return.push(Change::SuspendVoter(*voter_id, *until_epoch));
+                    }
+                Change::SlashLockedTokens(voter_id, amount) =>
+                    {
+                        changes_to_return.push(Change::SlashLockedTokens(*voter_id, amount.clone()));
+                    }
+                Change::ChangeMembershipBadge(membership_badge) =>
+                    {
+                        changes_to_return.push(Change::ChangeMembershipBadge(membership_badge.clone()));
+                    }
+                Change::ChangeEmissionSchedule(schedule) =>
+                    {
+                        changes_to_return.push(Change::ChangeEmissionSchedule(schedule.clone()));
+                    }
+                Change::BurnTreasuryTokens(amount) =>
+                    {
+                        changes_to_return.push(Change::BurnTreasuryTokens(amount.clone()));
+                    }
+                Change::RevokeExternalAdmin =>
+                    {
+                        changes_to_return.push(Change::RevokeExternalAdmin);
+                    }
+                Change::UpdateTokenMetadata(key, value) =>
+                    {
+                        changes_to_return.push(Change::UpdateTokenMetadata(key.clone(), value.clone()));
+                    }
+                Change::UpdateVoterCardMetadata(key, value) =>
+                    {
+                        changes_to_return.push(Change::UpdateVoterCardMetadata(key.clone(), value.clone()));
+                    }
+                Change::CreateResource(new_resource) =>
+                    {
+                        changes_to_return.push(Change::CreateResource(new_resource.clone()));
+                    }
+                Change::MintResource(address, amount) =>
+                    {
+                        changes_to_return.push(Change::MintResource(address.clone(), amount.clone()));
+                    }
+                Change::BurnResource(address, amount) =>
+                    {
+                        changes_to_return.push(Change::BurnResource(address.clone(), amount.clone()));
+                    }
+                Change::CallMethod(component, method, arguments, assets, return_kind) =>
+                    {
+                        changes_to_return.push(Change::CallMethod(component.clone(), method.clone(), arguments.clone(), assets.clone(), return_kind.clone()));
+                    }
+                Change::TransferNonFungibles(address, ids, to) =>
+                    {
+                        changes_to_return.push(Change::TransferNonFungibles(address.clone(), ids.clone(), *to));
+                    }
+                Change::PayAccount(address, amount, account) =>
+                    {
+                        changes_to_return.push(Change::PayAccount(address.clone(), amount.clone(), account.clone()));
+                    }
+                Change::ChangeClaimPeriod(period) =>
+                    {
+                        changes_to_return.push(Change::ChangeClaimPeriod(*period));
+                    }
+                Change::ChangeOutflowLimit(address, limit) =>
+                    {
+                        changes_to_return.push(Change::ChangeOutflowLimit(address.clone(), limit.clone()));
+                    }
+                Change::ChangeGuardianBadge(guardian_badge) =>
+                    {
+                        changes_to_return.push(Change::ChangeGuardianBadge(guardian_badge.clone()));
+                    }
+                Change::ChangeMaxGuardianPause(max_pause) =>
+                    {
+                        changes_to_return.push(Change::ChangeMaxGuardianPause(*max_pause));
+                    }
+                Change::SetPause(group, until_epoch) =>
+                    {
+                        changes_to_return.push(Change::SetPause(group.clone(), *until_epoch));
+                    }
+                Change::ChangeConvictionRules(decay, max_share, weight) =>
+                    {
+                        changes_to_return.push(Change::ChangeConvictionRules(*decay, *max_share, *weight));
+                    }
+            }
+        }
+
+        if changes_to_return.is_empty()
+        {
+            None
+        }
+        else
+        {
+            Some(changes_to_return)
+        }
+
+    }
+
+    /// Internal functions that corrects a user votes to make sure that they don't become too
+    /// powerful. A reference supply below e gives a cap of one vote, so that the logarithm stays
+    /// defined and positive.
+    ///
+    fn power_control_function(votes: Decimal, total_amount_of_tokens: Decimal) -> Decimal
+    {
+        let cap = cbrt(ln(total_amount_of_tokens.max(EULER_CONST)));
+
+        let mut corrected_votes = cap.min(votes);
+
+        let additional_votes = votes - corrected_votes;
+        if additional_votes > Decimal::zero()
+        {
+            // Add 1 to make sure that it is > 0
+            corrected_votes = corrected_votes +  cbrt(ln( additional_votes + 1));
+        }
+        corrected_votes
+    }
+
+}
+
+#[cfg(test)]
+mod tests
+{
+    use radix_engine::ledger::TypedInMemorySubstateStore;
+    use scrypto::core::Runtime;
+    use scrypto::crypto::Hash;
+    use scrypto::dec;
+    use scrypto::math::Decimal;
+    use scrypto_unit::TestRunner;
+    use scrypto::prelude::ResourceAddress;
+    use crate::ballot_box::{AmendmentRule, BallotBox, SupplyReference};
+    use crate::multiple_choice::{ProposalOption, VotingMode};
+    use crate::pause::MethodGroup;
+    use crate::proposal::{ProposalMetadata, ProposalStatus, Vote, Change};
+    use crate::voter_card::VoterCard;
+
+    fn metadata(summary: &str) -> ProposalMetadata
+    {
+        ProposalMetadata
+        {
+            title: String::from("Title of the proposal"),
+            summary: String::from(summary),
+            discussion_url: String::from("https://forum.example.com/proposal"),
+            content_hash: Hash([0; 32])
+        }
+    }
+
+    #[test]
+    fn test_new_proposal()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata.clone(),
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+
+        let proposal = ballot_box.proposals.get(0).unwrap();
+
+        assert_eq!(proposal.metadata.summary, proposal_metadata.summary);
+        assert_eq!(proposal.id, 0);
+        assert_eq!(ballot_box.new_proposal_id, 1);
+        assert!(proposal.status.is_suggestion_phase());
+        assert_eq!(proposal.epoch_expiration, 168);
+    }
+
+    #[test]
+    fn test_support_proposal()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
+        ballot_box.support_proposal(0, &mut voting_card, test_runner.get_current_epoch());
+
+        let proposal = ballot_box.proposals.get(0).unwrap();
+
+        assert_eq!(proposal.supporting_votes, voting_card.voting_power(test_runner.get_current_epoch()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_support_proposal_fail()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+
+        proposal.status = ProposalStatus::VotingPhase;
+
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(1234), test_runner.get_current_epoch());
+
+        ballot_box.support_proposal(0, &mut voting_card,test_runner.get_current_epoch());
+    }
+
+    #[test]
+    fn test_advance_with_proposal_support()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(100)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.supporting_votes = dec!(100);
+
+        let current = test_runner.get_current_epoch();
+        let new_epoch = current + ballot_box.support_period +1;
+        test_runner.set_current_epoch(new_epoch);
+
+        ballot_box.advance_with_proposal(0,test_runner.get_current_epoch());
+
+        let updated_proposal = ballot_box.proposals.get_mut(0).unwrap();
+        assert!(updated_proposal.status.is_voting_phase());
+        assert_eq!(updated_proposal.epoch_expiration, new_epoch + ballot_box.vote_period);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_advance_with_proposal_support_time_fail()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+
+        ballot_box.advance_with_proposal(0, test_runner.get_current_epoch());
+    }
+
+    #[test]
+    fn test_advance_with_proposal_support_amount_fail()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(100000)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.supporting_votes = dec!(100);
+
+        let current = test_runner.get_current_epoch();
+        let new_epoch = current + ballot_box.support_period +1;
+        test_runner.set_current_epoch(new_epoch);
+
+        ballot_box.advance_with_proposal(0, test_runner.get_current_epoch());
+
+        let updated_proposal = ballot_box.proposals.get(0).unwrap();
+        assert!(updated_proposal.status.is_suggestion_rejected());
+    }
+
+    #[test]
+    fn test_advance_with_proposal_vote()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
+        proposal.voted_for = dec!(1);
+
+        let current = test_runner.get_current_epoch();
+        let new_epoch = current + ballot_box.vote_period +1;
+        test_runner.set_current_epoch(new_epoch);
+
+        ballot_box.advance_with_proposal(0,test_runner.get_current_epoch());
+
+        let updated_proposal = ballot_box.proposals.get(0).unwrap();
+        assert!(updated_proposal.status.is_proposal_accepted());
+    }
+
+    #[test]
+    fn test_advance_with_proposal_vote_against()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
+        proposal.voted_against = dec!(1);
+
+        let current = test_runner.get_current_epoch();
+        let new_epoch = current + ballot_box.vote_period +1;
+        test_runner.set_current_epoch(new_epoch);
+
+        ballot_box.advance_with_proposal(0,test_runner.get_current_epoch());
+
+        let updated_proposal = ballot_box.proposals.get(0).unwrap();
+        assert!(updated_proposal.status.is_proposal_rejected());
+    }
+
+    #[test]
+    fn test_delegate_for_proposal()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
+
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
+        voting_card.approve(1, test_runner.get_current_epoch());
+
+        ballot_box.delegate_for_proposal(0, 1, &mut voting_card, test_runner.get_current_epoch());
+        let updated_proposal = ballot_box.proposals.get(0).unwrap();
+        assert_eq!(*updated_proposal.delegated_votes.get(&1).unwrap(), Decimal::zero());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_delegate_for_proposal_fail_cannot_delegate_to_id()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(1234), test_runner.get_current_epoch());
+
+        ballot_box.delegate_for_proposal(0, 1, &mut voting_card, test_runner.get_current_epoch());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_delegate_for_proposal_fail_not_voting_phase()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
+        voting_card.approve(1, test_runner.get_current_epoch());
+
+        ballot_box.delegate_for_proposal(0, 1, &mut voting_card, test_runner.get_current_epoch());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_delegate_for_proposal_fail_expired()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
+        proposal.epoch_expiration = 0;
+
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(1234), test_runner.get_current_epoch());
+        voting_card.approve(1, test_runner.get_current_epoch());
+
+        ballot_box.delegate_for_proposal(0,1, &mut voting_card, test_runner.get_current_epoch());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_delegate_for_proposal_fail_already_delegated()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
+
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(1234), test_runner.get_current_epoch());
+        voting_card.approve(1, test_runner.get_current_epoch());
+        voting_card.approve(2, test_runner.get_current_epoch());
+
+        ballot_box.delegate_for_proposal(0,1, &mut voting_card, test_runner.get_current_epoch());
+        ballot_box.delegate_for_proposal(0,2, &mut voting_card, test_runner.get_current_epoch());
+
+
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_delegate_for_proposal_fail_already_voted()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
+
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
+        voting_card.approve(1, test_runner.get_current_epoch());
+        voting_card.try_vote_for(0, &ProposalStatus::VotingPhase);
+
+        ballot_box.delegate_for_proposal(0,1, &mut voting_card, test_runner.get_current_epoch());
+    }
+
+    #[test]
+    fn test_vote_for_proposal()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
 
-    }
 
-    /// Internal functions that corrects a user votes to make sure that they don't become too
-    /// powerful
-    ///
-    fn power_control_function(votes: Decimal, total_amount_of_tokens: Decimal) -> Decimal
-    {
-        let cap = cbrt(ln(total_amount_of_tokens));
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
 
-        let mut corrected_votes = cap.min(votes);
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + 2016);
 
-        let additional_votes = votes - corrected_votes;
-        if additional_votes > Decimal::zero()
-        {
-            // Add 1 to make sure that it is > 0
-            corrected_votes = corrected_votes +  cbrt(ln( additional_votes + 1));
-        }
-        corrected_votes
-    }
+        let proposal_metadata = metadata("Test proposal");
+        ballot_box.make_proposal(
+            proposal_metadata,
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(10)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
 
-}
+        ballot_box.vote_for_proposal(0, &mut voting_card, Vote::For, test_runner.get_current_epoch());
 
-#[cfg(test)]
-mod tests
-{
-    use radix_engine::ledger::TypedInMemorySubstateStore;
-    use scrypto::core::Runtime;
-    use scrypto::dec;
-    use scrypto::math::Decimal;
-    use scrypto_unit::TestRunner;
-    use crate::ballot_box::BallotBox;
-    use crate::proposal::{ProposalStatus, Vote, Change};
-    use crate::voter_card::VoterCard;
+        let updated_proposal = ballot_box.proposals.get(0).unwrap();
+        assert!(updated_proposal.voted_for > dec!("1.3"));
+    }
 
     #[test]
-    fn test_new_proposal()
+    fn test_vote_for_proposal_with_delegated_votes_and_own_vote()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
+        let proposal_metadata = metadata("Test proposal");
+
+
+        let mut voting_card_1 = VoterCard::new(0);
+        voting_card_1.approve(1, test_runner.get_current_epoch());
+        voting_card_1.add_tokens(dec!(1), test_runner.get_current_epoch());
+
+        let mut voting_card_2 = VoterCard::new(1);
+        voting_card_2.add_tokens(dec!(1), test_runner.get_current_epoch());
+
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + 2016);
+
         ballot_box.make_proposal(
-            description.clone(),
+            proposal_metadata,
             vec![Change::ChangeVotePeriod(0)],
             test_runner.get_current_epoch(),
             dec!(10)
         );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
 
-        let proposal = ballot_box.proposals.get(0).unwrap();
+        ballot_box.delegate_for_proposal(0, 1, &mut voting_card_1, test_runner.get_current_epoch());
+        ballot_box.vote_for_proposal(0, &mut voting_card_2, Vote::For, test_runner.get_current_epoch());
 
-        assert_eq!(proposal.description, description);
-        assert_eq!(proposal.id, 0);
-        assert_eq!(ballot_box.new_proposal_id, 1);
-        assert!(proposal.status.is_suggestion_phase());
-        assert_eq!(proposal.epoch_expiration, 168);
+        let updated_proposal = ballot_box.proposals.get(0).unwrap();
+        assert!(updated_proposal.voted_for > dec!("0.6"));
     }
 
     #[test]
-    fn test_support_proposal()
+    fn test_vote_for_proposal_with_only_delegated_votes()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
+        let proposal_metadata = metadata("Test proposal");
+
+
+        let mut voting_card_1 = VoterCard::new(0);
+        voting_card_1.approve(1, test_runner.get_current_epoch());
+        voting_card_1.add_tokens(dec!(1), test_runner.get_current_epoch());
+
+        let mut voting_card_2 = VoterCard::new(1);
+        voting_card_2.add_tokens(dec!(1), test_runner.get_current_epoch());
+
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + 2016);
         ballot_box.make_proposal(
-            description,
+            proposal_metadata,
             vec![Change::ChangeVotePeriod(0)],
             test_runner.get_current_epoch(),
             dec!(10)
         );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
 
-        let mut voting_card = VoterCard::new(0);
-        voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
-        ballot_box.support_proposal(0, &mut voting_card, test_runner.get_current_epoch());
+        ballot_box.delegate_for_proposal(0, 1, &mut voting_card_1, test_runner.get_current_epoch());
 
-        let proposal = ballot_box.proposals.get(0).unwrap();
+        ballot_box.vote_for_proposal(0, &mut voting_card_2, Vote::For, test_runner.get_current_epoch());
 
-        assert_eq!(proposal.supporting_votes, voting_card.voting_power(test_runner.get_current_epoch()));
+        let updated_proposal = ballot_box.proposals.get(0).unwrap();
+        assert!(updated_proposal.voted_for > dec!("0.75"));
     }
 
     #[test]
     #[should_panic]
-    fn test_support_proposal_fail()
+    fn test_vote_for_proposal_fail_not_in_voting_phase()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
+        let proposal_metadata = metadata("Test proposal");
         ballot_box.make_proposal(
-            description,
+            proposal_metadata,
             vec![Change::ChangeVotePeriod(0)],
             test_runner.get_current_epoch(),
             dec!(10)
         );
+
+        let mut voting_card_1 = VoterCard::new(0);
+        voting_card_1.add_tokens(dec!(1), Runtime::current_epoch());
+
+        ballot_box.vote_for_proposal(0, &mut voting_card_1, Vote::Blank, test_runner.get_current_epoch());
+    }
+
+
+    #[test]
+    fn test_veto_proposal()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        ballot_box.execute_proposal(&vec![Change::ElectVetoCouncil(vec![1, 2, 3])]);
+        ballot_box.make_proposal(
+            metadata("Test proposal"),
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(100)
+        );
         let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        proposal.status = ProposalStatus::VotingPhase;
+        proposal.voted_for = dec!(100);
+
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + ballot_box.support_period + 1);
+        assert!(ballot_box.advance_with_proposal(0, test_runner.get_current_epoch()).is_none());
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_veto_phase());
+
+        ballot_box.veto_proposal(0, 1, test_runner.get_current_epoch());
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_veto_phase());
+        ballot_box.veto_proposal(0, 3, test_runner.get_current_epoch());
+
+        let proposal = ballot_box.proposals.get(0).unwrap();
+        assert!(proposal.status.is_vetoed());
+        assert_eq!(proposal.vetoed_by, vec![1, 3]);
+        assert_eq!(ballot_box.veto_log().len(), 2);
+        assert_ne!(ballot_box.vote_period, 0);
+    }
+
+    #[test]
+    fn test_veto_period_ends()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
 
+        let mut ballot_box = BallotBox::new();
+        ballot_box.execute_proposal(&vec![Change::ElectVetoCouncil(vec![1])]);
+        ballot_box.make_proposal(
+            metadata("Test proposal"),
+            vec![Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(100)
+        );
+        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
         proposal.status = ProposalStatus::VotingPhase;
 
-        let mut voting_card = VoterCard::new(0);
-        voting_card.add_tokens(dec!(1234), test_runner.get_current_epoch());
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + ballot_box.support_period + 1);
+        ballot_box.advance_with_proposal(0, test_runner.get_current_epoch());
 
-        ballot_box.support_proposal(0, &mut voting_card,test_runner.get_current_epoch());
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + ballot_box.veto_period);
+        ballot_box.advance_with_proposal(0, test_runner.get_current_epoch());
+
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_proposal_accepted());
+        assert_eq!(ballot_box.vote_period, 0);
     }
 
     #[test]
-    fn test_advance_with_proposal_support()
+    #[should_panic]
+    fn test_veto_not_council_member()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
+        ballot_box.execute_proposal(&vec![Change::ElectVetoCouncil(vec![1])]);
         ballot_box.make_proposal(
-            description,
+            metadata("Test proposal"),
             vec![Change::ChangeVotePeriod(0)],
             test_runner.get_current_epoch(),
             dec!(100)
         );
         let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
-        proposal.supporting_votes = dec!(100);
+        proposal.status = ProposalStatus::VetoPhase;
+        proposal.epoch_expiration = test_runner.get_current_epoch() + 10;
+
+        ballot_box.veto_proposal(0, 2, test_runner.get_current_epoch());
+    }
+
+    #[test]
+    fn test_optimistic_proposal_accepted()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+        let resource = ResourceAddress::Normal([0; 26]);
+
+        let mut ballot_box = BallotBox::new();
+        ballot_box.execute_proposal(&vec![Change::ChangeOptimisticLimit(resource, Some(dec!(100)))]);
+        ballot_box.make_optimistic_proposal(
+            metadata("Test proposal"),
+            vec![Change::AllowSpending(resource, dec!(60), 0), Change::AllowSpending(resource, dec!(40), 1)],
+            test_runner.get_current_epoch(),
+            dec!(100)
+        );
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_challenge_phase());
 
         let current = test_runner.get_current_epoch();
-        let new_epoch = current + ballot_box.support_period +1;
-        test_runner.set_current_epoch(new_epoch);
+        test_runner.set_current_epoch(current + ballot_box.challenge_period);
+        let changes = ballot_box.advance_with_proposal(0, test_runner.get_current_epoch()).unwrap();
 
-        ballot_box.advance_with_proposal(0,test_runner.get_current_epoch());
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_proposal_accepted());
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn test_optimistic_proposal_objected()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+        let resource = ResourceAddress::Normal([0; 26]);
+
+        let mut ballot_box = BallotBox::new();
+        ballot_box.execute_proposal(&vec![Change::ChangeOptimisticLimit(resource, Some(dec!(100)))]);
+
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + 2016);
+
+        ballot_box.make_optimistic_proposal(
+            metadata("Test proposal"),
+            vec![Change::AllowSpending(resource, dec!(60), 0)],
+            test_runner.get_current_epoch(),
+            dec!(100)
+        );
+        ballot_box.object_to_proposal(0, &voting_card, test_runner.get_current_epoch());
+
+        let proposal = ballot_box.proposals.get(0).unwrap();
+        assert!(proposal.status.is_voting_phase());
+        assert_eq!(proposal.epoch_expiration, test_runner.get_current_epoch() + ballot_box.vote_period);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_optimistic_proposal_not_eligible()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let test_runner = TestRunner::new(true, &mut store);
+        let resource = ResourceAddress::Normal([0; 26]);
+
+        let mut ballot_box = BallotBox::new();
+        ballot_box.execute_proposal(&vec![Change::ChangeOptimisticLimit(resource, Some(dec!(100)))]);
+        ballot_box.make_optimistic_proposal(
+            metadata("Test proposal"),
+            vec![Change::AllowSpending(resource, dec!(60), 0), Change::ChangeVotePeriod(0)],
+            test_runner.get_current_epoch(),
+            dec!(100)
+        );
+    }
+
+    #[test]
+    fn test_fast_track_proposal()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(100), test_runner.get_current_epoch());
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + 2016);
+
+        let mut ballot_box = BallotBox::new();
+        ballot_box.make_fast_track_proposal(
+            metadata("Test proposal"),
+            vec![Change::SetPause(MethodGroup::Claims, 500)],
+            test_runner.get_current_epoch(),
+            dec!(100)
+        );
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_voting_phase());
+
+        // The quorum is reached with the default rules even though the power control function
+        // reduces the votes far below it
+        ballot_box.vote_for_proposal(0, &mut voting_card, Vote::For, test_runner.get_current_epoch());
+        assert!(ballot_box.proposals.get(0).unwrap().voted_for < dec!(100) * ballot_box.fast_track_quorum);
+
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + ballot_box.fast_track_vote_period);
+        let changes = ballot_box.advance_with_proposal(0, test_runner.get_current_epoch()).unwrap();
+
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_proposal_accepted());
+        assert!(matches!(changes[0], Change::SetPause(_, 500)));
+    }
+
+    #[test]
+    fn test_fast_track_proposal_without_quorum()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut voting_card = VoterCard::new(0);
+        voting_card.add_tokens(dec!(10), test_runner.get_current_epoch());
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + 2016);
+
+        let mut ballot_box = BallotBox::new();
+        ballot_box.make_fast_track_proposal(
+            metadata("Test proposal"),
+            vec![Change::SetPause(MethodGroup::Claims, 500)],
+            test_runner.get_current_epoch(),
+            dec!(100)
+        );
+        ballot_box.vote_for_proposal(0, &mut voting_card, Vote::For, test_runner.get_current_epoch());
+
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + ballot_box.fast_track_vote_period);
+        assert!(ballot_box.advance_with_proposal(0, test_runner.get_current_epoch()).is_none());
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_proposal_rejected());
+    }
+
+    #[test]
+    fn test_fast_track_proposal_without_supermajority()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut voting_card_for = VoterCard::new(0);
+        voting_card_for.add_tokens(dec!(100), test_runner.get_current_epoch());
+        let mut voting_card_against = VoterCard::new(1);
+        voting_card_against.add_tokens(dec!(100), test_runner.get_current_epoch());
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + 2016);
+
+        let mut ballot_box = BallotBox::new();
+        ballot_box.make_fast_track_proposal(
+            metadata("Test proposal"),
+            vec![Change::SetPause(MethodGroup::Claims, 500)],
+            test_runner.get_current_epoch(),
+            dec!(200)
+        );
+        ballot_box.vote_for_proposal(0, &mut voting_card_for, Vote::For, test_runner.get_current_epoch());
+        ballot_box.vote_for_proposal(0, &mut voting_card_against, Vote::Against, test_runner.get_current_epoch());
+
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + ballot_box.fast_track_vote_period);
+        ballot_box.advance_with_proposal(0, test_runner.get_current_epoch());
+
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_proposal_rejected());
+    }
+
+    #[test]
+    fn test_prerequisites_executed()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        ballot_box.make_fast_track_proposal(metadata("First"), vec![Change::SetPause(MethodGroup::Claims, 500)], test_runner.get_current_epoch(), dec!(100));
+        ballot_box.make_fast_track_proposal(metadata("Second"), vec![Change::SetPause(MethodGroup::Unlocks, 500)], test_runner.get_current_epoch(), dec!(100));
+        ballot_box.set_prerequisites(1, vec![0]);
+        for proposal in ballot_box.proposals.iter_mut()
+        {
+            proposal.voted_for = dec!(30);
+            proposal.raw_votes = dec!(30);
+        }
+
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + ballot_box.fast_track_vote_period);
+        ballot_box.advance_with_proposal(0, test_runner.get_current_epoch());
+        let changes = ballot_box.advance_with_proposal(1, test_runner.get_current_epoch());
 
-        let updated_proposal = ballot_box.proposals.get_mut(0).unwrap();
-        assert!(updated_proposal.status.is_voting_phase());
-        assert_eq!(updated_proposal.epoch_expiration, new_epoch + ballot_box.vote_period);
+        assert!(ballot_box.proposals.get(1).unwrap().status.is_proposal_accepted());
+        assert!(matches!(changes.unwrap()[0], Change::SetPause(MethodGroup::Unlocks, _)));
     }
 
     #[test]
     #[should_panic]
-    fn test_advance_with_proposal_support_time_fail()
+    fn test_prerequisites_not_executed()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
-            test_runner.get_current_epoch(),
-            dec!(10)
-        );
+        ballot_box.make_fast_track_proposal(metadata("First"), vec![Change::SetPause(MethodGroup::Claims, 500)], test_runner.get_current_epoch(), dec!(100));
+        ballot_box.make_fast_track_proposal(metadata("Second"), vec![Change::SetPause(MethodGroup::Unlocks, 500)], test_runner.get_current_epoch(), dec!(100));
+        ballot_box.set_prerequisites(1, vec![0]);
+        let second = ballot_box.proposals.get_mut(1).unwrap();
+        second.voted_for = dec!(30);
+        second.raw_votes = dec!(30);
 
-        ballot_box.advance_with_proposal(0, test_runner.get_current_epoch());
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + ballot_box.fast_track_vote_period);
+        ballot_box.advance_with_proposal(1, test_runner.get_current_epoch());
     }
 
     #[test]
-    fn test_advance_with_proposal_support_amount_fail()
+    fn test_prerequisite_failed()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
-            test_runner.get_current_epoch(),
-            dec!(100000)
-        );
-        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
-        proposal.supporting_votes = dec!(100);
+        ballot_box.make_fast_track_proposal(metadata("First"), vec![Change::SetPause(MethodGroup::Claims, 500)], test_runner.get_current_epoch(), dec!(100));
+        ballot_box.make_fast_track_proposal(metadata("Second"), vec![Change::SetPause(MethodGroup::Unlocks, 500)], test_runner.get_current_epoch(), dec!(100));
+        ballot_box.set_prerequisites(1, vec![0]);
+        let second = ballot_box.proposals.get_mut(1).unwrap();
+        second.voted_for = dec!(30);
+        second.raw_votes = dec!(30);
 
         let current = test_runner.get_current_epoch();
-        let new_epoch = current + ballot_box.support_period +1;
-        test_runner.set_current_epoch(new_epoch);
-
+        test_runner.set_current_epoch(current + ballot_box.fast_track_vote_period);
         ballot_box.advance_with_proposal(0, test_runner.get_current_epoch());
+        let changes = ballot_box.advance_with_proposal(1, test_runner.get_current_epoch());
 
-        let updated_proposal = ballot_box.proposals.get(0).unwrap();
-        assert!(updated_proposal.status.is_suggestion_rejected());
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_proposal_rejected());
+        assert!(ballot_box.proposals.get(1).unwrap().status.is_proposal_rejected());
+        assert!(changes.is_none());
     }
 
     #[test]
-    fn test_advance_with_proposal_vote()
+    fn test_execution_failed()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
-            test_runner.get_current_epoch(),
-            dec!(10)
-        );
-        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
-        proposal.status = ProposalStatus::VotingPhase;
-        proposal.voted_for = dec!(1);
+        ballot_box.make_proposal(metadata("First"), vec![Change::ChangeVotePeriod(0), Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(10));
+        ballot_box.make_proposal(metadata("Second"), vec![Change::ChangeSupportPeriod(0)], test_runner.get_current_epoch(), dec!(10));
+        ballot_box.set_prerequisites(1, vec![0]);
+        for proposal in ballot_box.proposals.iter_mut()
+        {
+            proposal.status = ProposalStatus::VotingPhase;
+            proposal.voted_for = dec!(1);
+        }
 
         let current = test_runner.get_current_epoch();
-        let new_epoch = current + ballot_box.vote_period +1;
-        test_runner.set_current_epoch(new_epoch);
+        test_runner.set_current_epoch(current + ballot_box.vote_period + 1);
 
-        ballot_box.advance_with_proposal(0,test_runner.get_current_epoch());
+        // The changes are returned without being executed
+        let changes = ballot_box.advance_proposal(0, test_runner.get_current_epoch()).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_proposal_accepted());
+        assert_ne!(ballot_box.vote_period, 0);
 
-        let updated_proposal = ballot_box.proposals.get(0).unwrap();
-        assert!(updated_proposal.status.is_proposal_accepted());
+        ballot_box.fail_execution(0);
+        ballot_box.advance_with_proposal(1, test_runner.get_current_epoch());
+
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_execution_failed());
+        assert!(ballot_box.proposals.get(1).unwrap().status.is_proposal_rejected());
+        assert_ne!(ballot_box.vote_period, 0);
+        assert_ne!(ballot_box.support_period, 0);
     }
 
     #[test]
-    fn test_advance_with_proposal_vote_against()
+    fn test_fail_stuck_proposal()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
-            test_runner.get_current_epoch(),
-            dec!(10)
-        );
-        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
-        proposal.status = ProposalStatus::VotingPhase;
-        proposal.voted_against = dec!(1);
+        ballot_box.make_proposal(metadata("First"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(10));
+        ballot_box.make_proposal(metadata("Second"), vec![Change::ChangeSupportPeriod(0)], test_runner.get_current_epoch(), dec!(10));
+        ballot_box.set_prerequisites(1, vec![0]);
+        for proposal in ballot_box.proposals.iter_mut()
+        {
+            proposal.status = ProposalStatus::VotingPhase;
+            proposal.voted_for = dec!(1);
+        }
 
         let current = test_runner.get_current_epoch();
-        let new_epoch = current + ballot_box.vote_period +1;
-        test_runner.set_current_epoch(new_epoch);
+        test_runner.set_current_epoch(current + ballot_box.vote_period + ballot_box.execution_grace_period);
 
-        ballot_box.advance_with_proposal(0,test_runner.get_current_epoch());
+        ballot_box.fail_stuck_proposal(0, test_runner.get_current_epoch());
+        ballot_box.advance_with_proposal(1, test_runner.get_current_epoch());
 
-        let updated_proposal = ballot_box.proposals.get(0).unwrap();
-        assert!(updated_proposal.status.is_proposal_rejected());
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_execution_failed());
+        assert!(ballot_box.proposals.get(1).unwrap().status.is_proposal_rejected());
+        assert_ne!(ballot_box.support_period, 0);
     }
 
     #[test]
-    fn test_delegate_for_proposal()
+    #[should_panic]
+    fn test_fail_stuck_proposal_during_grace_period()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
-            test_runner.get_current_epoch(),
-            dec!(10)
-        );
-        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
+        ballot_box.make_proposal(metadata("First"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(10));
+        let proposal = ballot_box.proposals.get_mut(0).unwrap();
         proposal.status = ProposalStatus::VotingPhase;
+        proposal.voted_for = dec!(1);
 
-        let mut voting_card = VoterCard::new(0);
-        voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
-        voting_card.approve(1, test_runner.get_current_epoch());
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + ballot_box.vote_period + 1);
 
-        ballot_box.delegate_for_proposal(0, 1, &mut voting_card, test_runner.get_current_epoch());
-        let updated_proposal = ballot_box.proposals.get(0).unwrap();
-        assert_eq!(*updated_proposal.delegated_votes.get(&1).unwrap(), Decimal::zero());
+        ballot_box.fail_stuck_proposal(0, test_runner.get_current_epoch());
     }
 
     #[test]
-    #[should_panic]
-    fn test_delegate_for_proposal_fail_cannot_delegate_to_id()
+    fn test_author_amendment()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
+        let mut voting_card = VoterCard::new(1);
+        voting_card.add_tokens(dec!(100), test_runner.get_current_epoch());
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + 2016);
+
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
-            test_runner.get_current_epoch(),
-            dec!(10)
-        );
-        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
-        proposal.status = ProposalStatus::VotingPhase;
-        let mut voting_card = VoterCard::new(0);
-        voting_card.add_tokens(dec!(1234), test_runner.get_current_epoch());
+        ballot_box.make_proposal(metadata("Test proposal"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(1000));
+        ballot_box.set_author(0, 0);
+        ballot_box.support_proposal(0, &mut voting_card, test_runner.get_current_epoch());
 
-        ballot_box.delegate_for_proposal(0, 1, &mut voting_card, test_runner.get_current_epoch());
+        ballot_box.amend_proposal(0, 0, metadata("Amended proposal"), vec![Change::AllowMinting(dec!(3))], test_runner.get_current_epoch());
+        let proposal = ballot_box.get_proposal(0);
+        assert_eq!(proposal.metadata.summary, String::from("Amended proposal"));
+        assert_eq!(proposal.versions.len(), 2);
+        assert_eq!(proposal.versions[0].metadata.summary, String::from("Test proposal"));
+        assert_eq!(proposal.versions[1].metadata.summary, String::from("Amended proposal"));
+        assert_eq!(proposal.supporting_votes, Decimal::zero());
+
+        // The support was reset so the voter can support the new version
+        ballot_box.support_proposal(0, &mut voting_card, test_runner.get_current_epoch());
+        assert!(ballot_box.get_proposal(0).supporting_votes.is_positive());
     }
 
     #[test]
     #[should_panic]
-    fn test_delegate_for_proposal_fail_not_voting_phase()
+    fn test_amendment_empty_title()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
-        let mut test_runner = TestRunner::new(true, &mut store);
+        let test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
-            test_runner.get_current_epoch(),
-            dec!(10)
-        );
-        let mut voting_card = VoterCard::new(0);
-        voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
-        voting_card.approve(1, test_runner.get_current_epoch());
-
-        ballot_box.delegate_for_proposal(0, 1, &mut voting_card, test_runner.get_current_epoch());
+        ballot_box.make_proposal(metadata("Test proposal"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(1000));
+        let mut empty_title = metadata("Amended proposal");
+        empty_title.title = String::new();
+        ballot_box.suggest_amendment(0, empty_title, vec![], test_runner.get_current_epoch());
     }
 
     #[test]
     #[should_panic]
-    fn test_delegate_for_proposal_fail_expired()
+    fn test_amendment_not_author()
+    {
+        let mut store = TypedInMemorySubstateStore::with_bootstrap();
+        let test_runner = TestRunner::new(true, &mut store);
+
+        let mut ballot_box = BallotBox::new();
+        ballot_box.make_proposal(metadata("Test proposal"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(1000));
+        ballot_box.set_author(0, 0);
+        ballot_box.amend_proposal(0, 1, metadata("Amended proposal"), vec![], test_runner.get_current_epoch());
+    }
+
+    #[test]
+    fn test_supported_amendment()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
+        let mut voting_card = VoterCard::new(1);
+        voting_card.add_tokens(dec!(100), test_runner.get_current_epoch());
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + 2016);
+
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
-            test_runner.get_current_epoch(),
-            dec!(10)
-        );
-        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
-        proposal.status = ProposalStatus::VotingPhase;
-        proposal.epoch_expiration = 0;
+        ballot_box.execute_proposal(&vec![Change::ChangeAmendmentRule(AmendmentRule::KeepSupport)]);
+        ballot_box.make_proposal(metadata("Test proposal"), vec![Change::AllowMinting(dec!(5))], test_runner.get_current_epoch(), dec!(1000));
+        ballot_box.support_proposal(0, &mut voting_card, test_runner.get_current_epoch());
+        let support = ballot_box.get_proposal(0).supporting_votes;
 
-        let mut voting_card = VoterCard::new(0);
-        voting_card.add_tokens(dec!(1234), test_runner.get_current_epoch());
-        voting_card.approve(1, test_runner.get_current_epoch());
+        ballot_box.suggest_amendment(0, metadata("Amended proposal"), vec![Change::AllowMinting(dec!(3))], test_runner.get_current_epoch());
+        ballot_box.support_amendment(0, 0, &voting_card, test_runner.get_current_epoch());
 
-        ballot_box.delegate_for_proposal(0,1, &mut voting_card, test_runner.get_current_epoch());
+        let proposal = ballot_box.get_proposal(0);
+        assert!(proposal.amendments[0].applied);
+        assert_eq!(proposal.versions.len(), 2);
+        assert_eq!(proposal.metadata.summary, String::from("Amended proposal"));
+        assert_eq!(proposal.supporting_votes, support);
+        assert!(matches!(proposal.changes[0], Change::AllowMinting(amount) if amount == dec!(3)));
     }
 
     #[test]
     #[should_panic]
-    fn test_delegate_for_proposal_fail_already_delegated()
+    fn test_fast_track_proposal_not_eligible()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
-        let mut test_runner = TestRunner::new(true, &mut store);
+        let test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
+        ballot_box.make_fast_track_proposal(
+            metadata("Test proposal"),
+            vec![Change::AllowMinting(dec!(5))],
             test_runner.get_current_epoch(),
-            dec!(10)
+            dec!(100)
         );
-        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
-        proposal.status = ProposalStatus::VotingPhase;
-
-        let mut voting_card = VoterCard::new(0);
-        voting_card.add_tokens(dec!(1234), test_runner.get_current_epoch());
-        voting_card.approve(1, test_runner.get_current_epoch());
-        voting_card.approve(2, test_runner.get_current_epoch());
-
-        ballot_box.delegate_for_proposal(0,1, &mut voting_card, test_runner.get_current_epoch());
-        ballot_box.delegate_for_proposal(0,2, &mut voting_card, test_runner.get_current_epoch());
-
-
     }
 
     #[test]
     #[should_panic]
-    fn test_delegate_for_proposal_fail_already_voted()
+    fn test_fast_track_threshold_not_eligible()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
-        let mut test_runner = TestRunner::new(true, &mut store);
+        let test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
+        ballot_box.make_fast_track_proposal(
+            metadata("Test proposal"),
+            vec![Change::ChangeMinimumVoteThreshold(dec!(0))],
             test_runner.get_current_epoch(),
-            dec!(10)
+            dec!(100)
         );
-        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
-        proposal.status = ProposalStatus::VotingPhase;
-
-        let mut voting_card = VoterCard::new(0);
-        voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
-        voting_card.approve(1, test_runner.get_current_epoch());
-        voting_card.try_vote_for(0, &ProposalStatus::VotingPhase);
-
-        ballot_box.delegate_for_proposal(0,1, &mut voting_card, test_runner.get_current_epoch());
     }
 
     #[test]
-    fn test_vote_for_proposal()
+    fn test_multiple_choice_proposal()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
-        let mut ballot_box = BallotBox::new();
-
-
         let mut voting_card = VoterCard::new(0);
         voting_card.add_tokens(dec!(1000), test_runner.get_current_epoch());
-
         let current = test_runner.get_current_epoch();
         test_runner.set_current_epoch(current + 2016);
 
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
+        let mut ballot_box = BallotBox::new();
+        let options = vec![
+            ProposalOption { description: String::from("Short"), changes: vec![Change::ChangeVotePeriod(10)] },
+            ProposalOption { description: String::from("Long"), changes: vec![Change::ChangeVotePeriod(500)] }
+        ];
+        ballot_box.make_multiple_choice_proposal(
+            metadata("Test proposal"),
+            options,
+            VotingMode::Plurality,
             test_runner.get_current_epoch(),
-            dec!(10)
+            dec!(100)
         );
         let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
         proposal.status = ProposalStatus::VotingPhase;
 
-        ballot_box.vote_for_proposal(0, &mut voting_card, Vote::For, test_runner.get_current_epoch());
+        ballot_box.vote_for_proposal(0, &mut voting_card, Vote::Options(vec![1]), test_runner.get_current_epoch());
 
-        let updated_proposal = ballot_box.proposals.get(0).unwrap();
-        assert!(updated_proposal.voted_for > dec!("1.3"));
+        let current = test_runner.get_current_epoch();
+        test_runner.set_current_epoch(current + ballot_box.support_period + 1);
+        ballot_box.advance_with_proposal(0, test_runner.get_current_epoch());
+
+        assert!(ballot_box.proposals.get(0).unwrap().status.is_proposal_accepted());
+        assert_eq!(ballot_box.vote_period, 500);
     }
 
     #[test]
-    fn test_vote_for_proposal_with_delegated_votes_and_own_vote()
+    #[should_panic]
+    fn test_multiple_choice_proposal_yes_no_vote()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
-        let mut test_runner = TestRunner::new(true, &mut store);
+        let test_runner = TestRunner::new(true, &mut store);
 
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-
-
-        let mut voting_card_1 = VoterCard::new(0);
-        voting_card_1.approve(1, test_runner.get_current_epoch());
-        voting_card_1.add_tokens(dec!(1), test_runner.get_current_epoch());
-
-        let mut voting_card_2 = VoterCard::new(1);
-        voting_card_2.add_tokens(dec!(1), test_runner.get_current_epoch());
-
-        let current = test_runner.get_current_epoch();
-        test_runner.set_current_epoch(current + 2016);
-
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
+        let options = vec![
+            ProposalOption { description: String::from("Short"), changes: vec![Change::ChangeVotePeriod(10)] },
+            ProposalOption { description: String::from("Long"), changes: vec![Change::ChangeVotePeriod(500)] }
+        ];
+        ballot_box.make_multiple_choice_proposal(
+            metadata("Test proposal"),
+            options,
+            VotingMode::Approval,
             test_runner.get_current_epoch(),
-            dec!(10)
+            dec!(100)
         );
         let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
         proposal.status = ProposalStatus::VotingPhase;
 
-        ballot_box.delegate_for_proposal(0, 1, &mut voting_card_1, test_runner.get_current_epoch());
-        ballot_box.vote_for_proposal(0, &mut voting_card_2, Vote::For, test_runner.get_current_epoch());
-
-        let updated_proposal = ballot_box.proposals.get(0).unwrap();
-        assert!(updated_proposal.voted_for > dec!("0.6"));
+        let mut voting_card = VoterCard::new(0);
+        ballot_box.vote_for_proposal(0, &mut voting_card, Vote::For, test_runner.get_current_epoch());
     }
 
     #[test]
-    fn test_vote_for_proposal_with_only_delegated_votes()
+    fn test_poll()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
         let mut test_runner = TestRunner::new(true, &mut store);
 
-        let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-
-
         let mut voting_card_1 = VoterCard::new(0);
-        voting_card_1.approve(1, test_runner.get_current_epoch());
-        voting_card_1.add_tokens(dec!(1), test_runner.get_current_epoch());
-
         let mut voting_card_2 = VoterCard::new(1);
-        voting_card_2.add_tokens(dec!(1), test_runner.get_current_epoch());
-
+        voting_card_1.add_tokens(dec!(1000), test_runner.get_current_epoch());
+        voting_card_2.add_tokens(dec!(500), test_runner.get_current_epoch());
+        voting_card_2.approve(0, test_runner.get_current_epoch());
         let current = test_runner.get_current_epoch();
         test_runner.set_current_epoch(current + 2016);
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
-            test_runner.get_current_epoch(),
-            dec!(10)
-        );
-        let mut proposal = ballot_box.proposals.get_mut(0).unwrap();
-        proposal.status = ProposalStatus::VotingPhase;
 
-        ballot_box.delegate_for_proposal(0, 1, &mut voting_card_1, test_runner.get_current_epoch());
-
-        ballot_box.vote_for_proposal(0, &mut voting_card_2, Vote::For, test_runner.get_current_epoch());
+        let mut ballot_box = BallotBox::new();
+        ballot_box.make_poll(String::from("Test poll"), vec![String::from("Yes"), String::from("No")], test_runner.get_current_epoch(), dec!(1500));
+        ballot_box.delegate_for_poll(0, 0, &voting_card_2, test_runner.get_current_epoch());
+        ballot_box.vote_for_poll(0, &voting_card_1, 1, test_runner.get_current_epoch());
+
+        let total_voting_power = voting_card_1.voting_power(test_runner.get_current_epoch()) + voting_card_2.voting_power(test_runner.get_current_epoch());
+        let poll = ballot_box.get_poll(0);
+        assert_eq!(poll.tallies[0], Decimal::zero());
+        assert_eq!(poll.tallies[1], BallotBox::power_control_function(total_voting_power, dec!(1500)));
+
+        // The votes history of the VoterCards is not changed
+        assert!(voting_card_1.votes.is_empty());
+        assert!(voting_card_2.votes.is_empty());
+        assert!(ballot_box.proposals.is_empty());
+    }
 
-        let updated_proposal = ballot_box.proposals.get(0).unwrap();
-        assert!(updated_proposal.voted_for > dec!("0.75"));
+    #[test]
+    fn test_power_control_small_supply()
+    {
+        // Below e, the logarithm of the supply would give a cap under one vote or be undefined
+        assert_eq!(BallotBox::power_control_function(dec!(1), dec!("0.5")), dec!(1));
+        assert_eq!(BallotBox::power_control_function(dec!(1), dec!(2)), dec!(1));
     }
 
     #[test]
     #[should_panic]
-    fn test_vote_for_proposal_fail_not_in_voting_phase()
+    fn test_poll_vote_twice()
     {
         let mut store = TypedInMemorySubstateStore::with_bootstrap();
-        let mut test_runner = TestRunner::new(true, &mut store);
+        let test_runner = TestRunner::new(true, &mut store);
 
+        let voting_card = VoterCard::new(0);
         let mut ballot_box = BallotBox::new();
-        let description = String::from("Test proposal");
-        ballot_box.make_proposal(
-            description,
-            vec![Change::ChangeVotePeriod(0)],
-            test_runner.get_current_epoch(),
-            dec!(10)
-        );
-
-        let mut voting_card_1 = VoterCard::new(0);
-        voting_card_1.add_tokens(dec!(1), Runtime::current_epoch());
-
-        ballot_box.vote_for_proposal(0, &mut voting_card_1, Vote::Blank, test_runner.get_current_epoch());
+        ballot_box.make_poll(String::from("Test poll"), vec![String::from("Yes"), String::from("No")], test_runner.get_current_epoch(), dec!(1500));
+        ballot_box.vote_for_poll(0, &voting_card, 0, test_runner.get_current_epoch());
+        ballot_box.vote_for_poll(0, &voting_card, 1, test_runner.get_current_epoch());
     }
 
-
     #[test]
     fn execute_proposal_test()
     {
@@ -941,6 +2367,9 @@ mod tests
 
         ballot_box.execute_proposal(&vec![Change::ChangeSupportPeriod(0)]);
         assert_eq!(ballot_box.support_period, 0);
+
+        ballot_box.execute_proposal(&vec![Change::ChangeSupplyReference(SupplyReference::CirculatingSupply)]);
+        assert!(*ballot_box.supply_reference() == SupplyReference::CirculatingSupply);
     }
 
     #[test]
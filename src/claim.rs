@@ -0,0 +1,151 @@
+//! Defines the grants of assets that members of the DAO can claim before a given epoch.
+//! The granted amounts are reserved in the DAO and go back to its free balance once expired.
+//!
+//! Note: we made the choice to pass the `current_epoch` as an argument of the functions instead of
+//! calling `Runtime::current_epoch` to be able to unit test the file without using blueprints
+
+use scrypto::math::Decimal;
+
+/// An amount of asset that can be claimed until a given epoch
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct Grant
+{
+    /// Amount that can still be claimed
+    pub amount: Decimal,
+
+    /// Last epoch when the grant can be claimed
+    pub expiration_epoch: u64
+}
+
+/// Grants of a given asset made to a member of the DAO
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct ClaimableAsset
+{
+    /// Grants that have not been fully claimed, ordered by expiration epoch
+    pub grants: Vec<Grant>
+}
+
+impl ClaimableAsset
+{
+    /// Instantiates a new ClaimableAsset without any grant
+    pub fn new() -> ClaimableAsset
+    {
+        ClaimableAsset
+        {
+            grants: vec![]
+        }
+    }
+
+    /// Adds a grant that can be claimed until a given epoch
+    ///
+    /// # Arguments
+    /// * `amount` - amount granted
+    /// * `expiration_epoch` - last epoch when the grant can be claimed
+    pub fn add_grant(&mut self, amount: Decimal, expiration_epoch: u64)
+    {
+        assert!(amount.is_positive(), "The granted amount should be positive");
+        let index = self.grants.iter().position(|grant| grant.expiration_epoch > expiration_epoch).unwrap_or(self.grants.len());
+        self.grants.insert(index, Grant { amount, expiration_epoch });
+    }
+
+    /// Returns the amount that can be claimed at the current epoch
+    ///
+    /// # Arguments
+    /// * `current_epoch` - current epoch
+    pub fn claimable(&self, current_epoch: u64) -> Decimal
+    {
+        let mut total = Decimal::zero();
+        for grant in self.grants.iter().filter(|grant| grant.expiration_epoch >= current_epoch)
+        {
+            total = total + grant.amount;
+        }
+        total
+    }
+
+    /// Claims an amount from the grants that are not expired, starting with the ones expiring first
+    ///
+    /// # Arguments
+    /// * `amount` - amount to claim
+    /// * `current_epoch` - current epoch
+    pub fn claim(&mut self, amount: Decimal, current_epoch: u64)
+    {
+        assert!(amount <= self.claimable(current_epoch), "Cannot claim more than the claimable amount");
+
+        let mut amount_left = amount;
+        for grant in self.grants.iter_mut().filter(|grant| grant.expiration_epoch >= current_epoch)
+        {
+            let amount_taken = grant.amount.min(amount_left);
+            grant.amount = grant.amount - amount_taken;
+            amount_left = amount_left - amount_taken;
+        }
+
+        self.grants.retain(|grant| !grant.amount.is_zero());
+    }
+
+    /// Removes the expired grants and returns the amount that was not claimed
+    ///
+    /// # Arguments
+    /// * `current_epoch` - current epoch
+    pub fn remove_expired(&mut self, current_epoch: u64) -> Decimal
+    {
+        let mut expired_amount = Decimal::zero();
+        for grant in self.grants.iter().filter(|grant| grant.expiration_epoch < current_epoch)
+        {
+            expired_amount = expired_amount + grant.amount;
+        }
+
+        self.grants.retain(|grant| grant.expiration_epoch >= current_epoch);
+        expired_amount
+    }
+
+    /// Returns true if there is no grant left
+    pub fn is_empty(&self) -> bool
+    {
+        self.grants.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use scrypto::dec;
+    use scrypto::math::Decimal;
+    use crate::claim::ClaimableAsset;
+
+    #[test]
+    fn test_claim_expiring_first()
+    {
+        let mut asset = ClaimableAsset::new();
+        asset.add_grant(dec!(30), 200);
+        asset.add_grant(dec!(20), 100);
+        assert_eq!(asset.claimable(50), dec!(50));
+
+        asset.claim(dec!(25), 50);
+        assert_eq!(asset.grants.len(), 1);
+        assert_eq!(asset.grants[0].amount, dec!(25));
+        assert_eq!(asset.grants[0].expiration_epoch, 200);
+    }
+
+    #[test]
+    fn test_remove_expired()
+    {
+        let mut asset = ClaimableAsset::new();
+        asset.add_grant(dec!(30), 200);
+        asset.add_grant(dec!(20), 100);
+        assert_eq!(asset.claimable(150), dec!(30));
+
+        assert_eq!(asset.remove_expired(150), dec!(20));
+        assert_eq!(asset.remove_expired(150), Decimal::zero());
+        assert_eq!(asset.remove_expired(201), dec!(30));
+        assert!(asset.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_claim_expired_grant()
+    {
+        let mut asset = ClaimableAsset::new();
+        asset.add_grant(dec!(30), 100);
+        asset.claim(dec!(10), 101);
+    }
+}
@@ -0,0 +1,394 @@
+//! Defines the conviction voting used for continuous funding requests. Members stake their voting
+//! power on open funding requests and the conviction of a request builds up over epochs. A request
+//! is executed once its conviction passes a threshold that grows with the share of the treasury
+//! it requests.
+//!
+//! Note: we made the choice to pass the `current_epoch` as an argument of the functions instead of
+//! calling `Runtime::current_epoch` to be able to unit test the file without using blueprints
+
+use std::collections::HashMap;
+use scrypto::math::Decimal;
+use scrypto::prelude::ResourceAddress;
+
+/// A request for a grant from the treasury, funded through conviction voting
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct FundingRequest
+{
+    /// Id of the request
+    pub id: usize,
+
+    /// Description of the request
+    pub description: String,
+
+    /// Address of the requested resource
+    pub resource: ResourceAddress,
+
+    /// Requested amount
+    pub amount: Decimal,
+
+    /// VoterCard id of the member receiving the grant
+    pub beneficiary: u64,
+
+    /// Voting power staked on the request by each voter
+    pub stakes: HashMap<u64, Decimal>,
+
+    /// Total of the voting power staked on the request
+    pub total_staked: Decimal,
+
+    /// Conviction of the request at its last update
+    pub conviction: Decimal,
+
+    /// Epoch of the last update of the conviction
+    pub last_update: u64,
+
+    /// Whether the request was executed
+    pub executed: bool
+}
+
+/// Funding requests and rules of the conviction voting
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct ConvictionVoting
+{
+    /// List of all made funding requests
+    pub requests: Vec<FundingRequest>,
+
+    /// Total of the voting power staked by each voter on open requests
+    pub staked_by: HashMap<u64, Decimal>,
+
+    /// Share of the conviction kept from one epoch to the next
+    pub decay: Decimal,
+
+    /// Share of the treasury above which a request can never pass
+    pub max_share: Decimal,
+
+    /// Weight of the threshold that conviction has to pass
+    pub weight: Decimal
+}
+
+impl ConvictionVoting
+{
+    /// Instantiates a new ConvictionVoting without any request
+    ///
+    /// # Arguments
+    /// * `decay` - share of the conviction kept from one epoch to the next
+    /// * `max_share` - share of the treasury above which a request can never pass
+    /// * `weight` - weight of the threshold that conviction has to pass
+    pub fn new(decay: Decimal, max_share: Decimal, weight: Decimal) -> ConvictionVoting
+    {
+        let mut conviction_voting = ConvictionVoting
+        {
+            requests: vec![],
+            staked_by: HashMap::new(),
+            decay: Decimal::zero(),
+            max_share: Decimal::zero(),
+            weight: Decimal::zero()
+        };
+        conviction_voting.change_rules(decay, max_share, weight);
+        conviction_voting
+    }
+
+    /// Changes the rules of the conviction voting
+    ///
+    /// # Arguments
+    /// * `decay` - share of the conviction kept from one epoch to the next
+    /// * `max_share` - share of the treasury above which a request can never pass
+    /// * `weight` - weight of the threshold that conviction has to pass
+    pub fn change_rules(&mut self, decay: Decimal, max_share: Decimal, weight: Decimal)
+    {
+        Self::check_rules(decay, max_share, weight);
+
+        self.decay = decay;
+        self.max_share = max_share;
+        self.weight = weight;
+    }
+
+    /// Panics if the given rules of the conviction voting are invalid
+    ///
+    /// # Arguments
+    /// * `decay` - share of the conviction kept from one epoch to the next
+    /// * `max_share` - share of the treasury above which a request can never pass
+    /// * `weight` - weight of the threshold that conviction has to pass
+    pub fn check_rules(decay: Decimal, max_share: Decimal, weight: Decimal)
+    {
+        assert!(decay.is_positive() && decay < Decimal::one(), "The decay should be strictly between 0 and 1");
+        assert!(max_share.is_positive() && max_share <= Decimal::one(), "The max share should be between 0 and 1");
+        assert!(weight.is_positive(), "The weight should be positive");
+    }
+
+    /// Makes a new funding request without any stake
+    ///
+    /// # Arguments
+    /// * `description` - description of the request
+    /// * `resource` - address of the requested resource
+    /// * `amount` - requested amount
+    /// * `beneficiary` - VoterCard id of the member receiving the grant
+    /// * `current_epoch` - current epoch
+    pub fn make_request(&mut self, description: String, resource: ResourceAddress, amount: Decimal, beneficiary: u64, current_epoch: u64)
+    {
+        assert!(amount.is_positive(), "The requested amount should be positive");
+
+        let request = FundingRequest
+        {
+            id: self.requests.len(),
+            description,
+            resource,
+            amount,
+            beneficiary,
+            stakes: HashMap::new(),
+            total_staked: Decimal::zero(),
+            conviction: Decimal::zero(),
+            last_update: current_epoch,
+            executed: false
+        };
+        self.requests.push(request);
+    }
+
+    /// Stakes voting power on an open request. A voter cannot stake more than its voting power
+    /// over all the open requests.
+    ///
+    /// # Arguments
+    /// * `request_id` - id of the request
+    /// * `voter_id` - VoterCard id of the voter
+    /// * `voting_power` - current voting power of the voter
+    /// * `amount` - voting power to stake
+    /// * `current_epoch` - current epoch
+    pub fn stake(&mut self, request_id: usize, voter_id: u64, voting_power: Decimal, amount: Decimal, current_epoch: u64)
+    {
+        assert!(amount.is_positive(), "The staked amount should be positive");
+        let already_staked = self.staked_by.get(&voter_id).cloned().unwrap_or(Decimal::zero());
+        assert!(already_staked + amount <= voting_power, "Cannot stake more than your voting power");
+
+        self.update(request_id, current_epoch);
+        let request = self.requests.get_mut(request_id).unwrap();
+        assert!(!request.executed, "This request was already executed");
+
+        let staked = request.stakes.entry(voter_id).or_insert(Decimal::zero());
+        *staked = *staked + amount;
+        request.total_staked = request.total_staked + amount;
+        self.staked_by.insert(voter_id, already_staked + amount);
+    }
+
+    /// Removes voting power staked on an open request
+    ///
+    /// # Arguments
+    /// * `request_id` - id of the request
+    /// * `voter_id` - VoterCard id of the voter
+    /// * `amount` - voting power to remove
+    /// * `current_epoch` - current epoch
+    pub fn unstake(&mut self, request_id: usize, voter_id: u64, amount: Decimal, current_epoch: u64)
+    {
+        self.update(request_id, current_epoch);
+        let request = self.requests.get_mut(request_id).unwrap();
+        assert!(!request.executed, "This request was already executed");
+
+        let staked = request.stakes.get_mut(&voter_id).expect("You did not stake on this request");
+        assert!(amount.is_positive() && amount <= *staked, "Cannot remove more than your stake");
+
+        *staked = *staked - amount;
+        request.total_staked = request.total_staked - amount;
+        self.staked_by.insert(voter_id, self.staked_by[&voter_id] - amount);
+    }
+
+    /// Removes all the stakes of a voter on the open requests
+    ///
+    /// # Arguments
+    /// * `voter_id` - VoterCard id of the voter
+    /// * `current_epoch` - current epoch
+    pub fn withdraw_all_stakes(&mut self, voter_id: u64, current_epoch: u64)
+    {
+        for request_id in 0..self.requests.len()
+        {
+            let staked = match self.requests[request_id].stakes.get(&voter_id)
+            {
+                Some(staked) if !self.requests[request_id].executed && staked.is_positive() => *staked,
+                _ => { continue; }
+            };
+            self.unstake(request_id, voter_id, staked, current_epoch);
+        }
+    }
+
+    /// Returns the conviction that a request has to pass to be executed, or None if the request
+    /// asks for too large a share of the treasury to ever pass. A constant stake converges to a
+    /// conviction of stake / (1 - decay), so the threshold is scaled the same way: a request passes
+    /// once about weight / gap² of the reference supply stakes on it, where gap is the difference
+    /// between the max share and the requested share.
+    ///
+    /// # Arguments
+    /// * `request_id` - id of the request
+    /// * `treasury_amount` - amount of the requested resource owned by the DAO
+    /// * `reference_supply` - reference supply of DAO tokens
+    pub fn threshold(&self, request_id: usize, treasury_amount: Decimal, reference_supply: Decimal) -> Option<Decimal>
+    {
+        assert!(request_id < self.requests.len(), "This funding request does not exist!");
+
+        if !treasury_amount.is_positive()
+        {
+            return None;
+        }
+
+        let share = self.requests[request_id].amount / treasury_amount;
+        if share >= self.max_share
+        {
+            None
+        }
+        else
+        {
+            let gap = self.max_share - share;
+            Some(self.weight * reference_supply / ((Decimal::one() - self.decay) * gap * gap))
+        }
+    }
+
+    /// Updates the conviction of a request and marks it as executed if its conviction passed the
+    /// threshold. The stakes on an executed request are released. Returns true if the request has
+    /// to be executed.
+    ///
+    /// # Arguments
+    /// * `request_id` - id of the request
+    /// * `treasury_amount` - amount of the requested resource owned by the DAO
+    /// * `reference_supply` - reference supply of DAO tokens
+    /// * `current_epoch` - current epoch
+    pub fn try_execute(&mut self, request_id: usize, treasury_amount: Decimal, reference_supply: Decimal, current_epoch: u64) -> bool
+    {
+        self.update(request_id, current_epoch);
+
+        let threshold = match self.threshold(request_id, treasury_amount, reference_supply)
+        {
+            None => { return false; }
+            Some(threshold) => threshold
+        };
+
+        let request = self.requests.get_mut(request_id).unwrap();
+        if request.executed || request.conviction < threshold
+        {
+            return false;
+        }
+
+        request.executed = true;
+        for (voter_id, staked) in request.stakes.iter()
+        {
+            self.staked_by.insert(*voter_id, self.staked_by[voter_id] - *staked);
+        }
+        true
+    }
+
+    /// Internal function that brings the conviction of a request to the current epoch. Each epoch,
+    /// the conviction decays and the total of the stakes is added to it.
+    fn update(&mut self, request_id: usize, current_epoch: u64)
+    {
+        assert!(request_id < self.requests.len(), "This funding request does not exist!");
+
+        let decay = self.decay;
+        let request = self.requests.get_mut(request_id).unwrap();
+        if request.executed || current_epoch <= request.last_update
+        {
+            return;
+        }
+
+        let kept = Self::pow(decay, current_epoch - request.last_update);
+        request.conviction = request.conviction * kept + request.total_staked * (Decimal::one() - kept) / (Decimal::one() - decay);
+        request.last_update = current_epoch;
+    }
+
+    /// Internal function that raises a Decimal to an integer power by squaring
+    fn pow(base: Decimal, exponent: u64) -> Decimal
+    {
+        let mut result = Decimal::one();
+        let mut square = base;
+        let mut exponent = exponent;
+        while exponent > 0
+        {
+            if exponent % 2 == 1
+            {
+                result = result * square;
+            }
+            square = square * square;
+            exponent /= 2;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use scrypto::dec;
+    use scrypto::prelude::ResourceAddress;
+    use crate::conviction::ConvictionVoting;
+
+    #[test]
+    fn test_conviction_builds_up()
+    {
+        let mut conviction_voting = ConvictionVoting::new(dec!("0.5"), dec!("0.2"), dec!("0.005"));
+        conviction_voting.make_request(String::from("Grant"), ResourceAddress::Normal([0; 26]), dec!(10), 0, 0);
+        conviction_voting.stake(0, 1, dec!(100), dec!(10), 0);
+
+        // Threshold is 0.005 * 100 / ((1 - 0.5) * (0.2 - 0.1)^2) = 100 and conviction tends to 10 / (1 - 0.5) = 20
+        assert!(!conviction_voting.try_execute(0, dec!(100), dec!(100), 1));
+        assert_eq!(conviction_voting.requests[0].conviction, dec!(10));
+        assert!(!conviction_voting.try_execute(0, dec!(100), dec!(100), 2));
+        assert_eq!(conviction_voting.requests[0].conviction, dec!(15));
+
+        conviction_voting.stake(0, 2, dec!(100), dec!(50), 2);
+        assert!(!conviction_voting.try_execute(0, dec!(100), dec!(100), 3));
+        assert!(conviction_voting.try_execute(0, dec!(100), dec!(100), 10));
+        assert!(conviction_voting.requests[0].executed);
+        assert_eq!(*conviction_voting.staked_by.get(&2).unwrap(), dec!(0));
+    }
+
+    #[test]
+    fn test_minority_stake_never_passes()
+    {
+        let mut conviction_voting = ConvictionVoting::new(dec!("0.5"), dec!("0.2"), dec!("0.01"));
+        conviction_voting.make_request(String::from("Grant"), ResourceAddress::Normal([0; 26]), dec!(10), 0, 0);
+        conviction_voting.stake(0, 1, dec!(100), dec!(60), 0);
+
+        // Threshold is 0.01 * 100 / ((1 - 0.5) * (0.2 - 0.1)^2) = 200 and conviction tends to 60 / (1 - 0.5) = 120
+        assert_eq!(conviction_voting.threshold(0, dec!(100), dec!(100)).unwrap(), dec!(200));
+        assert!(!conviction_voting.try_execute(0, dec!(100), dec!(100), 1000));
+        assert!(!conviction_voting.requests[0].executed);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_rules()
+    {
+        ConvictionVoting::check_rules(dec!(1), dec!("0.2"), dec!("0.01"));
+    }
+
+    #[test]
+    fn test_too_large_share()
+    {
+        let mut conviction_voting = ConvictionVoting::new(dec!("0.5"), dec!("0.2"), dec!("0.01"));
+        conviction_voting.make_request(String::from("Grant"), ResourceAddress::Normal([0; 26]), dec!(30), 0, 0);
+        conviction_voting.stake(0, 1, dec!(1000), dec!(1000), 0);
+        assert!(conviction_voting.threshold(0, dec!(100), dec!(100)).is_none());
+        assert!(!conviction_voting.try_execute(0, dec!(100), dec!(100), 100));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stake_over_voting_power()
+    {
+        let mut conviction_voting = ConvictionVoting::new(dec!("0.5"), dec!("0.2"), dec!("0.01"));
+        conviction_voting.make_request(String::from("Grant"), ResourceAddress::Normal([0; 26]), dec!(10), 0, 0);
+        conviction_voting.make_request(String::from("Other grant"), ResourceAddress::Normal([0; 26]), dec!(10), 0, 0);
+        conviction_voting.stake(0, 1, dec!(10), dec!(6), 0);
+        conviction_voting.stake(1, 1, dec!(10), dec!(6), 0);
+    }
+
+    #[test]
+    fn test_withdraw_all_stakes()
+    {
+        let mut conviction_voting = ConvictionVoting::new(dec!("0.5"), dec!("0.2"), dec!("0.01"));
+        conviction_voting.make_request(String::from("Grant"), ResourceAddress::Normal([0; 26]), dec!(10), 0, 0);
+        conviction_voting.make_request(String::from("Other grant"), ResourceAddress::Normal([0; 26]), dec!(10), 0, 0);
+        conviction_voting.stake(0, 1, dec!(10), dec!(4), 0);
+        conviction_voting.stake(1, 1, dec!(10), dec!(6), 0);
+        conviction_voting.withdraw_all_stakes(1, 1);
+
+        assert_eq!(*conviction_voting.staked_by.get(&1).unwrap(), dec!(0));
+        assert_eq!(conviction_voting.requests[0].total_staked, dec!(0));
+        assert_eq!(conviction_voting.requests[1].total_staked, dec!(0));
+        assert_eq!(conviction_voting.requests[1].conviction, dec!(6));
+    }
+}
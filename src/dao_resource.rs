@@ -0,0 +1,94 @@
+//! Defines the resources that the DAO can create through proposals
+
+use scrypto::prelude::{Decimal, DIVISIBILITY_MAXIMUM};
+use scrypto::NonFungibleData;
+
+/// Maximum number of items of a non fungible resource that can be minted at once
+pub const MAX_MINTED_ITEMS: u64 = 100;
+
+/// Kind of a resource created by the DAO
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub enum ResourceKind
+{
+    /// Fungible resource with the given divisibility
+    Fungible(u8),
+
+    /// Non fungible resource whose items are DaoItems
+    NonFungible
+}
+
+impl ResourceKind
+{
+    /// Returns whether the given amount can be minted at once. For non fungible resources, it
+    /// should be an integer number of items of at most MAX_MINTED_ITEMS.
+    ///
+    /// # Arguments
+    /// * `amount` - amount to mint
+    pub fn can_mint(&self, amount: Decimal) -> bool
+    {
+        match self
+        {
+            ResourceKind::Fungible(_) => !amount.is_negative(),
+            ResourceKind::NonFungible => !amount.is_negative() && amount.floor() == amount && amount <= Decimal::from(MAX_MINTED_ITEMS)
+        }
+    }
+}
+
+/// Description of a resource to be created by the DAO. The mint and burn authority of the resource
+/// belong to the DAO and its initial supply is put in the assets managed by the DAO.
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct NewResource
+{
+    /// Name of the resource
+    pub name: String,
+
+    /// Symbol of the resource
+    pub symbol: String,
+
+    /// Kind of the resource
+    pub kind: ResourceKind,
+
+    /// Initial supply of the resource. For non fungible resources, it is the number of items to mint
+    pub initial_supply: Decimal
+}
+
+impl NewResource
+{
+    /// Panics if the resource cannot be created: a divisibility above the maximum or an initial
+    /// supply that cannot be minted
+    pub fn check(&self)
+    {
+        if let ResourceKind::Fungible(divisibility) = self.kind
+        {
+            assert!(divisibility <= DIVISIBILITY_MAXIMUM, "The divisibility cannot be greater than {}", DIVISIBILITY_MAXIMUM);
+        }
+        assert!(self.kind.can_mint(self.initial_supply), "This initial supply cannot be minted");
+    }
+}
+
+/// An item of a non fungible resource created by the DAO
+#[derive(NonFungibleData)]
+pub struct DaoItem
+{
+    /// Epoch when the item was minted
+    pub minted_epoch: u64
+}
+
+#[cfg(test)]
+mod tests
+{
+    use scrypto::dec;
+    use crate::dao_resource::{MAX_MINTED_ITEMS, ResourceKind};
+
+    #[test]
+    fn test_can_mint()
+    {
+        assert!(ResourceKind::Fungible(18).can_mint(dec!("0.5")));
+        assert!(!ResourceKind::Fungible(18).can_mint(dec!(-1)));
+
+        assert!(ResourceKind::NonFungible.can_mint(dec!(3)));
+        assert!(ResourceKind::NonFungible.can_mint(MAX_MINTED_ITEMS.into()));
+        assert!(!ResourceKind::NonFungible.can_mint(dec!("2.5")));
+        assert!(!ResourceKind::NonFungible.can_mint((MAX_MINTED_ITEMS + 1).into()));
+    }
+}
@@ -0,0 +1,232 @@
+//! Defines the emission schedules that can be used to emit new Styx tokens over time.
+//! Any emission is capped by the max supply of the DAO token.
+//!
+//! Note: we made the choice to pass the `current_epoch` as an argument of the functions instead of
+//! calling `Runtime::current_epoch` to be able to unit test the file without using blueprints
+
+use scrypto::dec;
+use scrypto::math::Decimal;
+use crate::decimal_maths::{exp, ln};
+
+/// Schedule following which new DAO tokens are emitted
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub enum EmissionSchedule
+{
+    /// Emits every epoch a given share of the tokens already emitted
+    Inflation(Decimal),
+
+    /// Emits every epoch a given amount of tokens, halved every given number of epochs
+    Halving(Decimal, u64)
+}
+
+impl EmissionSchedule
+{
+    /// Panics if the schedule emits a negative amount or halves every zero epoch
+    pub fn check(&self)
+    {
+        match self
+        {
+            EmissionSchedule::Inflation(rate) => { assert!(*rate >= Decimal::zero(), "The inflation rate cannot be negative"); }
+            EmissionSchedule::Halving(amount, period) =>
+                {
+                    assert!(*amount >= Decimal::zero(), "The emitted amount cannot be negative");
+                    assert!(*period > 0, "The halving period cannot be zero");
+                }
+        }
+    }
+}
+
+/// An emission schedule with the epochs needed to compute how much should be emitted
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct Emission
+{
+    /// Schedule of the emission
+    pub schedule: EmissionSchedule,
+
+    /// Epoch when the emission started
+    pub start_epoch: u64,
+
+    /// Epoch of the last emission
+    pub last_emission_epoch: u64
+}
+
+impl Emission
+{
+    /// Instantiates a new Emission starting at the current epoch
+    ///
+    /// # Arguments
+    /// * `schedule` - schedule of the emission
+    /// * `current_epoch` - current epoch
+    ///
+    /// # Examples
+    /// ```
+    /// use scrypto::dec;
+    /// use styx::emission::{Emission, EmissionSchedule};
+    /// let emission = Emission::new(EmissionSchedule::Halving(dec!(10), 100), 0);
+    /// ```
+    pub fn new(schedule: EmissionSchedule, current_epoch: u64) -> Emission
+    {
+        schedule.check();
+
+        Emission
+        {
+            schedule,
+            start_epoch: current_epoch,
+            last_emission_epoch: current_epoch
+        }
+    }
+
+    /// Returns the amount of tokens to emit since the last emission and marks them as emitted.
+    /// The returned amount never makes the emitted tokens go over the max supply.
+    ///
+    /// # Arguments
+    /// * `emitted_tokens` - amount of tokens already emitted
+    /// * `max_supply` - max supply of the token
+    /// * `current_epoch` - current epoch
+    ///
+    /// # Examples
+    /// ```
+    /// use scrypto::dec;
+    /// use styx::emission::{Emission, EmissionSchedule};
+    /// let mut emission = Emission::new(EmissionSchedule::Halving(dec!(10), 100), 0);
+    /// let to_emit = emission.emit(dec!(100), dec!(1000), 10);
+    /// assert_eq!(to_emit, dec!(100));
+    /// ```
+    pub fn emit(&mut self, emitted_tokens: Decimal, max_supply: Decimal, current_epoch: u64) -> Decimal
+    {
+        let amount = self.amount_to_emit(emitted_tokens, max_supply, current_epoch);
+        self.last_emission_epoch = current_epoch;
+        amount
+    }
+
+    /// Returns the amount of tokens to emit since the last emission, capped by the max supply
+    ///
+    /// # Arguments
+    /// * `emitted_tokens` - amount of tokens already emitted
+    /// * `max_supply` - max supply of the token
+    /// * `current_epoch` - current epoch
+    pub fn amount_to_emit(&self, emitted_tokens: Decimal, max_supply: Decimal, current_epoch: u64) -> Decimal
+    {
+        if current_epoch <= self.last_emission_epoch || emitted_tokens >= max_supply
+        {
+            return Decimal::zero();
+        }
+
+        let remaining_supply = max_supply - emitted_tokens;
+        let amount = match self.schedule
+        {
+            EmissionSchedule::Inflation(rate) =>
+                {
+                    Self::inflation_amount(rate, emitted_tokens, max_supply, current_epoch - self.last_emission_epoch)
+                }
+            EmissionSchedule::Halving(initial_amount, period) =>
+                {
+                    self.halving_amount(initial_amount, period, current_epoch)
+                }
+        };
+
+        amount.min(remaining_supply)
+    }
+
+    /// Internal function that computes the amount emitted by a compounded inflation over a number
+    /// of epochs. If the emitted tokens would go over the max supply, the max supply is returned
+    /// instead to avoid overflows.
+    fn inflation_amount(rate: Decimal, emitted_tokens: Decimal, max_supply: Decimal, epochs: u64) -> Decimal
+    {
+        if rate.is_zero() || emitted_tokens.is_zero()
+        {
+            return Decimal::zero();
+        }
+
+        // emitted * (1 + rate)^epochs = emitted * exp(epochs * ln(1 + rate))
+        let exponent = Decimal::from(epochs) * ln(rate + 1);
+        if exponent >= ln(max_supply / emitted_tokens)
+        {
+            max_supply
+        }
+        else
+        {
+            emitted_tokens * exp(exponent) - emitted_tokens
+        }
+    }
+
+    /// Internal function that computes the amount emitted by a halving schedule between the last
+    /// emission and the current epoch
+    fn halving_amount(&self, initial_amount: Decimal, period: u64, current_epoch: u64) -> Decimal
+    {
+        let mut total = Decimal::zero();
+        let mut epoch = self.last_emission_epoch;
+
+        while epoch < current_epoch
+        {
+            let period_index = (epoch - self.start_epoch) / period;
+
+            // After 128 halvings, nothing representable is emitted anymore
+            if period_index >= 128
+            {
+                break;
+            }
+
+            let amount_per_epoch = initial_amount / dec!(2).powi(period_index as i64);
+            if amount_per_epoch.is_zero()
+            {
+                break;
+            }
+
+            let period_end = self.start_epoch + (period_index + 1) * period;
+            let epochs = current_epoch.min(period_end) - epoch;
+            total = total + amount_per_epoch * epochs;
+            epoch += epochs;
+        }
+
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use scrypto::dec;
+    use scrypto::math::Decimal;
+    use crate::emission::{Emission, EmissionSchedule};
+
+    #[test]
+    fn test_inflation()
+    {
+        let mut emission = Emission::new(EmissionSchedule::Inflation(dec!("0.01")), 0);
+        let to_emit = emission.emit(dec!(100), dec!(1000), 2);
+
+        let diff = to_emit - dec!("2.01");
+        assert!(diff.abs() < dec!("0.000000001"));
+        assert_eq!(emission.last_emission_epoch, 2);
+    }
+
+    #[test]
+    fn test_halving()
+    {
+        let mut emission = Emission::new(EmissionSchedule::Halving(dec!(10), 10), 0);
+        assert_eq!(emission.emit(dec!(0), dec!(1000), 25), dec!("162.5"));
+
+        // Emitting again during the same epoch does nothing
+        assert_eq!(emission.emit(dec!("162.5"), dec!(1000), 25), Decimal::zero());
+        assert_eq!(emission.emit(dec!("162.5"), dec!(1000), 30), dec!("12.5"));
+    }
+
+    #[test]
+    fn test_emission_capped_by_max_supply()
+    {
+        let mut halving = Emission::new(EmissionSchedule::Halving(dec!(1000), 1000), 0);
+        assert_eq!(halving.emit(dec!(100), dec!(10000), 1000000), dec!(9900));
+
+        let mut inflation = Emission::new(EmissionSchedule::Inflation(dec!("0.5")), 0);
+        assert_eq!(inflation.emit(dec!(100), dec!(10000), 1000000), dec!(9900));
+        assert_eq!(inflation.emit(dec!(10000), dec!(10000), 2000000), Decimal::zero());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_halving_zero_period()
+    {
+        Emission::new(EmissionSchedule::Halving(dec!(10), 0), 0);
+    }
+}
@@ -18,6 +18,14 @@
 extern crate core;
 pub mod styx_dao;
 pub mod ballot_box;
+pub mod claim;
+pub mod conviction;
+pub mod dao_resource;
 pub mod decimal_maths;
+pub mod emission;
+pub mod multiple_choice;
+pub mod outflow;
+pub mod pause;
+pub mod poll;
 pub mod proposal;
 pub mod voter_card;
\ No newline at end of file
@@ -0,0 +1,280 @@
+//! Defines the multiple-choice proposals, where voters choose between several options each
+//! carrying its own changes. Only the changes of the winning option are executed.
+
+use scrypto::math::Decimal;
+use crate::proposal::Change;
+
+/// Way the votes of a multiple-choice proposal are cast and counted
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone, PartialEq, Debug)]
+pub enum VotingMode
+{
+    /// Each voter chooses one option. The option with the most votes wins
+    Plurality,
+
+    /// Each voter approves any number of options. The option with the most approvals wins
+    Approval,
+
+    /// Each voter ranks the options. The winner is found by instant runoff
+    RankedChoice
+}
+
+/// An option of a multiple-choice proposal
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct ProposalOption
+{
+    /// Description of the option
+    pub description: String,
+
+    /// Changes to be enacted if the option wins
+    pub changes: Vec<Change>
+}
+
+/// Options and votes of a multiple-choice proposal
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct MultipleChoice
+{
+    /// Voting mode of the proposal
+    pub mode: VotingMode,
+
+    /// Options to choose from
+    pub options: Vec<ProposalOption>,
+
+    /// Votes cast for each option, for plurality and approval voting
+    pub tallies: Vec<Decimal>,
+
+    /// Ranked ballots with their weight, for ranked-choice voting
+    pub ballots: Vec<(Vec<usize>, Decimal)>,
+
+    /// Total of the votes cast
+    pub total_votes: Decimal
+}
+
+impl MultipleChoice
+{
+    /// Instantiates a new MultipleChoice without any vote
+    ///
+    /// # Arguments
+    /// * `mode` - voting mode
+    /// * `options` - options to choose from
+    pub fn new(mode: VotingMode, options: Vec<ProposalOption>) -> MultipleChoice
+    {
+        assert!(options.len() >= 2, "A multiple-choice proposal needs at least two options");
+
+        MultipleChoice
+        {
+            mode,
+            tallies: vec![Decimal::zero(); options.len()],
+            options,
+            ballots: vec![],
+            total_votes: Decimal::zero()
+        }
+    }
+
+    /// Casts a vote for the given options. For plurality voting, exactly one option should be
+    /// given. For ranked-choice voting, the options are ordered by preference.
+    ///
+    /// # Arguments
+    /// * `choices` - chosen options
+    /// * `weight` - weight of the vote
+    pub fn cast(&mut self, choices: Vec<usize>, weight: Decimal)
+    {
+        assert!(!choices.is_empty(), "At least one option should be chosen");
+        for (index, choice) in choices.iter().enumerate()
+        {
+            assert!(*choice < self.options.len(), "This option does not exist");
+            assert!(!choices[..index].contains(choice), "An option cannot be chosen twice");
+        }
+
+        match self.mode
+        {
+            VotingMode::Plurality =>
+                {
+                    assert_eq!(choices.len(), 1, "Only one option can be chosen");
+                    self.tallies[choices[0]] = self.tallies[choices[0]] + weight;
+                }
+            VotingMode::Approval =>
+                {
+                    for choice in choices
+                    {
+                        self.tallies[choice] = self.tallies[choice] + weight;
+                    }
+                }
+            VotingMode::RankedChoice =>
+                {
+                    self.ballots.push((choices, weight));
+                }
+        }
+
+        self.total_votes = self.total_votes + weight;
+    }
+
+    /// Returns the winning option, or None if there is a tie or no vote
+    pub fn winner(&self) -> Option<usize>
+    {
+        match self.mode
+        {
+            VotingMode::Plurality | VotingMode::Approval => Self::unique_max(&self.tallies, &vec![true; self.options.len()]),
+            VotingMode::RankedChoice => self.instant_runoff()
+        }
+    }
+
+    /// Internal function that runs an instant runoff on the ranked ballots. Each round, the ballots
+    /// count for their most preferred remaining option and the option with the least votes is
+    /// eliminated, until an option has a majority of the remaining ballots.
+    fn instant_runoff(&self) -> Option<usize>
+    {
+        let mut remaining = vec![true; self.options.len()];
+
+        loop
+        {
+            let mut round_tallies = vec![Decimal::zero(); self.options.len()];
+            let mut round_total = Decimal::zero();
+            for (ranking, weight) in self.ballots.iter()
+            {
+                if let Some(choice) = ranking.iter().find(|choice| remaining[**choice])
+                {
+                    round_tallies[*choice] = round_tallies[*choice] + *weight;
+                    round_total = round_total + *weight;
+                }
+            }
+
+            if round_total.is_zero()
+            {
+                return None;
+            }
+
+            let remaining_count = remaining.iter().filter(|option| **option).count();
+            let leader = Self::unique_max(&round_tallies, &remaining);
+            match leader
+            {
+                Some(option) if round_tallies[option] * 2 > round_total || remaining_count <= 2 => { return leader; }
+                _ if remaining_count <= 2 => { return None; }
+                _ => {}
+            }
+
+            // Eliminate the remaining option with the least votes, the last one in case of a tie
+            let mut to_eliminate = None;
+            for option in (0..self.options.len()).rev().filter(|option| remaining[*option])
+            {
+                match to_eliminate
+                {
+                    None => { to_eliminate = Some(option); }
+                    Some(current) => { if round_tallies[option] < round_tallies[current] { to_eliminate = Some(option); } }
+                }
+            }
+            remaining[to_eliminate.unwrap()] = false;
+        }
+    }
+
+    /// Internal function that returns the index of the unique highest positive tally among the
+    /// eligible options
+    fn unique_max(tallies: &Vec<Decimal>, eligible: &Vec<bool>) -> Option<usize>
+    {
+        let mut best: Option<usize> = None;
+        let mut tie = false;
+        for (option, tally) in tallies.iter().enumerate().filter(|(option, _)| eligible[*option])
+        {
+            match best
+            {
+                None => { best = Some(option); }
+                Some(current) =>
+                    {
+                        if *tally > tallies[current]
+                        {
+                            best = Some(option);
+                            tie = false;
+                        }
+                        else if *tally == tallies[current]
+                        {
+                            tie = true;
+                        }
+                    }
+            }
+        }
+
+        match best
+        {
+            Some(option) if !tie && tallies[option].is_positive() => Some(option),
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use scrypto::dec;
+    use crate::multiple_choice::{MultipleChoice, ProposalOption, VotingMode};
+
+    fn options(count: usize) -> Vec<ProposalOption>
+    {
+        (0..count).map(|i| ProposalOption { description: format!("Option {}", i), changes: vec![] }).collect()
+    }
+
+    #[test]
+    fn test_plurality()
+    {
+        let mut multiple_choice = MultipleChoice::new(VotingMode::Plurality, options(3));
+        multiple_choice.cast(vec![0], dec!(10));
+        multiple_choice.cast(vec![2], dec!(15));
+        multiple_choice.cast(vec![1], dec!(12));
+        assert_eq!(multiple_choice.winner(), Some(2));
+        assert_eq!(multiple_choice.total_votes, dec!(37));
+    }
+
+    #[test]
+    fn test_plurality_tie()
+    {
+        let mut multiple_choice = MultipleChoice::new(VotingMode::Plurality, options(2));
+        multiple_choice.cast(vec![0], dec!(10));
+        multiple_choice.cast(vec![1], dec!(10));
+        assert_eq!(multiple_choice.winner(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_plurality_several_choices()
+    {
+        let mut multiple_choice = MultipleChoice::new(VotingMode::Plurality, options(3));
+        multiple_choice.cast(vec![0, 1], dec!(10));
+    }
+
+    #[test]
+    fn test_approval()
+    {
+        let mut multiple_choice = MultipleChoice::new(VotingMode::Approval, options(3));
+        multiple_choice.cast(vec![0, 1], dec!(10));
+        multiple_choice.cast(vec![2], dec!(15));
+        multiple_choice.cast(vec![1], dec!(6));
+        assert_eq!(multiple_choice.winner(), Some(1));
+    }
+
+    #[test]
+    fn test_ranked_choice()
+    {
+        let mut multiple_choice = MultipleChoice::new(VotingMode::RankedChoice, options(3));
+        multiple_choice.cast(vec![0, 1], dec!(40));
+        multiple_choice.cast(vec![1, 2], dec!(25));
+        multiple_choice.cast(vec![2, 1], dec!(35));
+
+        // Option 1 is eliminated first and its ballots go to option 2
+        assert_eq!(multiple_choice.winner(), Some(2));
+    }
+
+    #[test]
+    fn test_ranked_choice_majority()
+    {
+        let mut multiple_choice = MultipleChoice::new(VotingMode::RankedChoice, options(3));
+        multiple_choice.cast(vec![0], dec!(60));
+        multiple_choice.cast(vec![1, 0], dec!(40));
+        assert_eq!(multiple_choice.winner(), Some(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_option_chosen_twice()
+    {
+        let mut multiple_choice = MultipleChoice::new(VotingMode::RankedChoice, options(3));
+        multiple_choice.cast(vec![0, 0], dec!(10));
+    }
+}
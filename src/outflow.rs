@@ -0,0 +1,217 @@
+//! Defines the limits on the amount of a resource that can leave the DAO treasury or be minted by
+//! governance over a rolling window of epochs.
+//!
+//! Note: we made the choice to pass the `current_epoch` as an argument of the functions instead of
+//! calling `Runtime::current_epoch` to be able to unit test the file without using blueprints
+
+use std::collections::HashMap;
+use scrypto::math::Decimal;
+use scrypto::prelude::ResourceAddress;
+
+/// Limit on the outflows of a resource over a rolling window of epochs
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct OutflowLimit
+{
+    /// Maximum amount that can flow out during a window
+    pub max_amount: Decimal,
+
+    /// Number of epochs of the rolling window
+    pub window: u64,
+
+    /// Outflows made during the current window, with the epoch when they were made
+    pub outflows: Vec<(u64, Decimal)>
+}
+
+impl OutflowLimit
+{
+    /// Instantiates a new OutflowLimit without any recorded outflow
+    ///
+    /// # Arguments
+    /// * `max_amount` - maximum amount that can flow out during a window
+    /// * `window` - number of epochs of the rolling window
+    pub fn new(max_amount: Decimal, window: u64) -> OutflowLimit
+    {
+        Self::check(max_amount, window);
+
+        OutflowLimit
+        {
+            max_amount,
+            window,
+            outflows: vec![]
+        }
+    }
+
+    /// Panics if the limit is negative or if the window is zero epoch long
+    ///
+    /// # Arguments
+    /// * `max_amount` - maximum amount that can flow out during a window
+    /// * `window` - number of epochs of the rolling window
+    pub fn check(max_amount: Decimal, window: u64)
+    {
+        assert!(!max_amount.is_negative(), "The outflow limit cannot be negative");
+        assert!(window > 0, "The outflow window cannot be zero");
+    }
+
+    /// Returns the amount that can still flow out at the current epoch
+    ///
+    /// # Arguments
+    /// * `current_epoch` - current epoch
+    pub fn available(&self, current_epoch: u64) -> Decimal
+    {
+        let mut total = Decimal::zero();
+        for (_, amount) in self.outflows.iter().filter(|(epoch, _)| self.is_in_window(*epoch, current_epoch))
+        {
+            total = total + *amount;
+        }
+
+        if total >= self.max_amount { Decimal::zero() } else { self.max_amount - total }
+    }
+
+    /// Records an outflow if it does not make the outflows of the window go over the limit and
+    /// returns whether it was recorded
+    ///
+    /// # Arguments
+    /// * `amount` - amount flowing out
+    /// * `current_epoch` - current epoch
+    pub fn record(&mut self, amount: Decimal, current_epoch: u64) -> bool
+    {
+        if amount > self.available(current_epoch)
+        {
+            return false;
+        }
+
+        let window = self.window;
+        self.outflows.retain(|(epoch, _)| *epoch + window > current_epoch);
+        self.outflows.push((current_epoch, amount));
+        true
+    }
+
+    /// Returns whether a new limit would be at least as strict as this one: a lower or equal
+    /// maximum amount over a longer or equal window
+    ///
+    /// # Arguments
+    /// * `max_amount` - maximum amount of the new limit
+    /// * `window` - number of epochs of the window of the new limit
+    pub fn is_tightened_by(&self, max_amount: Decimal, window: u64) -> bool
+    {
+        max_amount <= self.max_amount && window >= self.window
+    }
+
+    /// Internal function that returns true if an outflow made at a given epoch is in the window
+    /// ending at the current epoch
+    fn is_in_window(&self, epoch: u64, current_epoch: u64) -> bool
+    {
+        epoch + self.window > current_epoch
+    }
+}
+
+/// Sets or removes the outflow limit of a resource. Outflows recorded under a previous limit are kept.
+///
+/// # Arguments
+/// * `limits` - outflow limits of the resources
+/// * `address` - address of the resource
+/// * `limit` - maximum amount and number of epochs of the rolling window, or None
+pub fn change_outflow_limit(limits: &mut HashMap<ResourceAddress, OutflowLimit>, address: ResourceAddress, limit: Option<(Decimal, u64)>)
+{
+    match limit
+    {
+        None => { limits.remove(&address); }
+        Some((max_amount, window)) =>
+            {
+                let mut new_limit = OutflowLimit::new(max_amount, window);
+                if let Some(old_limit) = limits.get(&address)
+                {
+                    new_limit.outflows = old_limit.outflows.clone();
+                }
+                limits.insert(address, new_limit);
+            }
+    }
+}
+
+/// Returns whether setting a new outflow limit on a resource only tightens its current limit.
+/// Setting a limit on a resource without any limit always tightens it.
+///
+/// # Arguments
+/// * `limits` - outflow limits of the resources
+/// * `address` - address of the resource
+/// * `max_amount` - maximum amount of the new limit
+/// * `window` - number of epochs of the window of the new limit
+pub fn tightens_outflow_limit(limits: &HashMap<ResourceAddress, OutflowLimit>, address: ResourceAddress, max_amount: Decimal, window: u64) -> bool
+{
+    match limits.get(&address)
+    {
+        None => true,
+        Some(limit) => limit.is_tightened_by(max_amount, window)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::collections::HashMap;
+    use scrypto::dec;
+    use scrypto::math::Decimal;
+    use scrypto::prelude::ResourceAddress;
+    use crate::outflow::{change_outflow_limit, tightens_outflow_limit, OutflowLimit};
+
+    #[test]
+    fn test_rolling_window()
+    {
+        let mut limit = OutflowLimit::new(dec!(100), 10);
+        assert!(limit.record(dec!(60), 0));
+        assert!(limit.record(dec!(40), 5));
+        assert_eq!(limit.available(9), Decimal::zero());
+
+        // The first outflow leaves the window
+        assert_eq!(limit.available(10), dec!(60));
+        assert!(limit.record(dec!(60), 10));
+        assert_eq!(limit.outflows.len(), 2);
+        assert_eq!(limit.available(15), dec!(40));
+    }
+
+    #[test]
+    fn test_over_limit()
+    {
+        let mut limit = OutflowLimit::new(dec!(100), 10);
+        assert!(limit.record(dec!(60), 0));
+        assert!(!limit.record(dec!(50), 9));
+        assert_eq!(limit.outflows.len(), 1);
+        assert_eq!(limit.available(9), dec!(40));
+    }
+
+    #[test]
+    fn test_tightens_outflow_limit()
+    {
+        let address = ResourceAddress::Normal([0; 26]);
+        let mut limits: HashMap<ResourceAddress, OutflowLimit> = HashMap::new();
+        assert!(tightens_outflow_limit(&limits, address, dec!(1000), 1));
+
+        change_outflow_limit(&mut limits, address, Some((dec!(100), 10)));
+        assert!(tightens_outflow_limit(&limits, address, dec!(50), 20));
+        assert!(!tightens_outflow_limit(&limits, address, dec!(150), 10));
+        assert!(!tightens_outflow_limit(&limits, address, dec!(100), 5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_window()
+    {
+        OutflowLimit::check(dec!(100), 0);
+    }
+
+    #[test]
+    fn test_change_outflow_limit()
+    {
+        let address = ResourceAddress::Normal([0; 26]);
+        let mut limits: HashMap<ResourceAddress, OutflowLimit> = HashMap::new();
+        change_outflow_limit(&mut limits, address, Some((dec!(100), 10)));
+        assert!(limits.get_mut(&address).unwrap().record(dec!(60), 0));
+
+        // Outflows recorded under the previous limit are kept
+        change_outflow_limit(&mut limits, address, Some((dec!(80), 10)));
+        assert_eq!(limits.get(&address).unwrap().available(5), dec!(20));
+
+        change_outflow_limit(&mut limits, address, None);
+        assert!(limits.get(&address).is_none());
+    }
+}
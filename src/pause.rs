@@ -0,0 +1,156 @@
+//! Defines the emergency pauses of the DAO methods. Guardians can pause a group of methods for a
+//! bounded number of epochs and only a proposal can extend a pause. Pauses end automatically.
+//!
+//! Note: we made the choice to pass the `current_epoch` as an argument of the functions instead of
+//! calling `Runtime::current_epoch` to be able to unit test the file without using blueprints
+
+use std::collections::HashMap;
+
+/// Groups of methods of the DAO that can be paused
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum MethodGroup
+{
+    /// Claims of granted assets
+    Claims,
+
+    /// Unlocks of DAO tokens
+    Unlocks,
+
+    /// Execution of accepted proposals
+    ProposalExecution,
+
+    /// Minting of DAO tokens and of resources created by the DAO
+    Minting
+}
+
+/// Current pauses of the method groups
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct Pauses
+{
+    /// Maximum number of epochs of a pause made by the guardians
+    pub max_guardian_pause: u64,
+
+    /// Epoch until which each method group is paused
+    pub paused_until: HashMap<MethodGroup, u64>,
+
+    /// Epoch from which the guardians can pause each method group again
+    pub guardian_cooldown_until: HashMap<MethodGroup, u64>
+}
+
+impl Pauses
+{
+    /// Instantiates Pauses where nothing is paused
+    ///
+    /// # Arguments
+    /// * `max_guardian_pause` - maximum number of epochs of a pause made by the guardians
+    pub fn new(max_guardian_pause: u64) -> Pauses
+    {
+        Pauses
+        {
+            max_guardian_pause,
+            paused_until: HashMap::new(),
+            guardian_cooldown_until: HashMap::new()
+        }
+    }
+
+    /// Returns whether a method group is paused at the current epoch
+    ///
+    /// # Arguments
+    /// * `group` - method group to check
+    /// * `current_epoch` - current epoch
+    pub fn is_paused(&self, group: &MethodGroup, current_epoch: u64) -> bool
+    {
+        match self.paused_until.get(group)
+        {
+            None => false,
+            Some(until_epoch) => current_epoch < *until_epoch
+        }
+    }
+
+    /// Pauses a method group on behalf of the guardians. The guardians cannot extend a pause and
+    /// have to wait for `max_guardian_pause` epochs after the end of their last pause to pause the
+    /// same group again.
+    ///
+    /// # Arguments
+    /// * `group` - method group to pause
+    /// * `duration` - number of epochs of the pause
+    /// * `current_epoch` - current epoch
+    pub fn guardian_pause(&mut self, group: MethodGroup, duration: u64, current_epoch: u64)
+    {
+        assert!(duration > 0 && duration <= self.max_guardian_pause, "Guardians cannot pause for this number of epochs");
+        assert!(!self.is_paused(&group, current_epoch), "This method group is already paused. Only a proposal can extend the pause");
+
+        match self.guardian_cooldown_until.get(&group)
+        {
+            None => {}
+            Some(cooldown) => { assert!(current_epoch >= *cooldown, "The guardians cannot pause this method group yet"); }
+        }
+
+        let until_epoch = current_epoch + duration;
+        self.paused_until.insert(group.clone(), until_epoch);
+        self.guardian_cooldown_until.insert(group, until_epoch + self.max_guardian_pause);
+    }
+
+    /// Pauses a method group until a given epoch, as decided by a proposal. Pausing until a past
+    /// epoch lifts the pause
+    ///
+    /// # Arguments
+    /// * `group` - method group to pause
+    /// * `until_epoch` - epoch until which the group is paused
+    pub fn set_pause(&mut self, group: MethodGroup, until_epoch: u64)
+    {
+        self.paused_until.insert(group, until_epoch);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use crate::pause::{MethodGroup, Pauses};
+
+    #[test]
+    fn test_guardian_pause_ends_automatically()
+    {
+        let mut pauses = Pauses::new(100);
+        pauses.guardian_pause(MethodGroup::Claims, 50, 10);
+        assert!(pauses.is_paused(&MethodGroup::Claims, 59));
+        assert!(!pauses.is_paused(&MethodGroup::Claims, 60));
+        assert!(!pauses.is_paused(&MethodGroup::Unlocks, 20));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_guardian_cannot_extend()
+    {
+        let mut pauses = Pauses::new(100);
+        pauses.guardian_pause(MethodGroup::Claims, 50, 10);
+        pauses.guardian_pause(MethodGroup::Claims, 50, 30);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_guardian_cooldown()
+    {
+        let mut pauses = Pauses::new(100);
+        pauses.guardian_pause(MethodGroup::Claims, 50, 10);
+        pauses.guardian_pause(MethodGroup::Claims, 50, 100);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_guardian_pause_too_long()
+    {
+        let mut pauses = Pauses::new(100);
+        pauses.guardian_pause(MethodGroup::Minting, 101, 10);
+    }
+
+    #[test]
+    fn test_proposal_extends_pause()
+    {
+        let mut pauses = Pauses::new(100);
+        pauses.guardian_pause(MethodGroup::Claims, 50, 10);
+        pauses.set_pause(MethodGroup::Claims, 500);
+        assert!(pauses.is_paused(&MethodGroup::Claims, 499));
+        assert!(!pauses.is_paused(&MethodGroup::Claims, 500));
+    }
+}
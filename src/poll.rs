@@ -0,0 +1,49 @@
+//! Defines what is a Poll: a non-binding question to the members of the DAO with free-form
+//! options. A Poll has a single voting window and is never executed.
+
+use std::collections::HashMap;
+use scrypto::math::Decimal;
+
+/// A non-binding poll
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct Poll
+{
+    /// Id of the poll
+    pub id: usize,
+
+    /// Question asked by the poll
+    pub description: String,
+
+    /// Options to choose from
+    pub options: Vec<String>,
+
+    /// Votes cast for each option
+    pub tallies: Vec<Decimal>,
+
+    /// VoterCards ids of the users who voted or delegated their votes
+    pub voters: Vec<u64>,
+
+    /// Number of votes delegated to a voter
+    pub delegated_votes: HashMap<u64, Decimal>,
+
+    /// To whom someone has delegated
+    pub delegation_to: HashMap<u64, u64>,
+
+    /// Epoch of the end of the poll
+    pub epoch_expiration: u64,
+
+    /// Reference supply of tokens at the Poll creation
+    pub reference_supply: Decimal
+}
+
+impl Poll
+{
+    /// Returns whether the poll is still open at the current epoch
+    ///
+    /// # Arguments
+    /// * `current_epoch` - current epoch
+    pub fn is_open(&self, current_epoch: u64) -> bool
+    {
+        self.epoch_expiration > current_epoch
+    }
+}
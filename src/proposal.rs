@@ -1,18 +1,43 @@
 //! Defines here what is a proposal and how it can change the DAO
 
 use std::collections::HashMap;
+use scrypto::crypto::Hash;
 use scrypto::dec;
-use scrypto::prelude::{Decimal, ResourceAddress};
+use scrypto::prelude::{ComponentAddress, Decimal, NonFungibleId, ResourceAddress};
+use crate::ballot_box::{AmendmentRule, SupplyReference};
+use crate::dao_resource::NewResource;
+use crate::emission::EmissionSchedule;
+use crate::multiple_choice::MultipleChoice;
+use crate::pause::MethodGroup;
 
 /// A voter can not only vote For or Against a Proposal but also Blank.
 /// Blank votes are not taken into account when counting votes but we could add a reward for voting
 /// and Blank votes would count to get the reward.
+/// Multiple-choice proposals are voted with the options chosen by the voter: a single option for
+/// plurality voting, the approved options for approval voting and the options ordered by
+/// preference for ranked-choice voting.
 #[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
 pub enum Vote
 {
     For,
     Against,
-    Blank
+    Blank,
+    Options(Vec<usize>)
+}
+
+
+/// What a method called by the DAO through `Change::CallMethod` returns
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub enum ReturnKind
+{
+    /// The method returns nothing, or a value that contains no bucket
+    Nothing,
+
+    /// The method returns a single bucket
+    Bucket,
+
+    /// The method returns a list of buckets
+    Buckets
 }
 
 
@@ -24,7 +49,11 @@ pub enum ProposalStatus
     SuggestionRejected,
     VotingPhase,
     ProposalRejected,
-    ProposalAccepted
+    ProposalAccepted,
+    VetoPhase,
+    Vetoed,
+    ChallengePhase,
+    ExecutionFailed
 }
 
 /// Proposed change to parameters of votes. If a proposal is accepted and changes are made to the
@@ -44,11 +73,188 @@ pub enum Change
     /// Changes the minimum amount of votes that have to be casted to consider a vote valid
     ChangeMinimumVoteThreshold(Decimal),
 
+    /// Changes the supply used as a reference for new proposals
+    ChangeSupplyReference(SupplyReference),
+
     /// Allows claiming of a certain amount of resource by a voter id
     AllowSpending(ResourceAddress, Decimal, u64),
 
     /// Allows the minting of new DAO tokens
-    AllowMinting(Decimal)
+    AllowMinting(Decimal),
+
+    /// Suspends a voter id until a given epoch. Suspending until a past epoch lifts the suspension
+    SuspendVoter(u64, u64),
+
+    /// Slashes a certain amount of tokens locked by a voter id and sends them to the DAO treasury
+    SlashLockedTokens(u64, Decimal),
+
+    /// Changes the badge required to mint a VoterCard. None opens the membership to everyone
+    ChangeMembershipBadge(Option<ResourceAddress>),
+
+    /// Changes the emission schedule of DAO tokens. None stops the emission
+    ChangeEmissionSchedule(Option<EmissionSchedule>),
+
+    /// Burns a certain amount of DAO tokens owned by the DAO
+    BurnTreasuryTokens(Decimal),
+
+    /// Revokes the powers of the external admin badge
+    RevokeExternalAdmin,
+
+    /// Sets a metadata field of the DAO token
+    UpdateTokenMetadata(String, String),
+
+    /// Sets a metadata field of the VoterCard resource
+    UpdateVoterCardMetadata(String, String),
+
+    /// Creates a new resource managed by the DAO
+    CreateResource(NewResource),
+
+    /// Mints a certain amount of a resource created by the DAO
+    MintResource(ResourceAddress, Decimal),
+
+    /// Burns a certain amount of a resource created by the DAO and owned by the DAO
+    BurnResource(ResourceAddress, Decimal),
+
+    /// Calls a method of a component with the authority of the DAO. The buckets taken from the
+    /// assets owned by the DAO are passed as first argument of the method, followed by the
+    /// SBOR-encoded list of other arguments. The buckets returned by the method, as described by
+    /// the ReturnKind, are put in the assets owned by the DAO
+    CallMethod(ComponentAddress, String, Vec<u8>, Vec<(ResourceAddress, Decimal)>, ReturnKind),
+
+    /// Allows claiming of given non fungible assets by a voter id
+    TransferNonFungibles(ResourceAddress, Vec<NonFungibleId>, u64),
+
+    /// Deposits a certain amount of resource directly into an account
+    PayAccount(ResourceAddress, Decimal, ComponentAddress),
+
+    /// Changes the number of epochs during which granted assets can be claimed
+    ChangeClaimPeriod(u64),
+
+    /// Changes the maximum amount of a resource that can leave the treasury or be minted by
+    /// proposals over a rolling window of epochs. None removes the limit
+    ChangeOutflowLimit(ResourceAddress, Option<(Decimal, u64)>),
+
+    /// Changes the badge held by the guardians. None removes the guardians
+    ChangeGuardianBadge(Option<ResourceAddress>),
+
+    /// Changes the maximum number of epochs of a pause made by the guardians
+    ChangeMaxGuardianPause(u64),
+
+    /// Pauses a method group until a given epoch. Pausing until a past epoch lifts the pause
+    SetPause(MethodGroup, u64),
+
+    /// Elects the VoterCards ids of the veto council. An empty list removes the council
+    ElectVetoCouncil(Vec<u64>),
+
+    /// Changes the period during which the veto council can veto an accepted proposal
+    ChangeVetoPeriod(u64),
+
+    /// Changes the maximum amount of a resource that an optimistic proposal can spend. None
+    /// prevents optimistic proposals from spending the resource
+    ChangeOptimisticLimit(ResourceAddress, Option<Decimal>),
+
+    /// Changes the period during which an optimistic proposal can be objected to
+    ChangeChallengePeriod(u64),
+
+    /// Changes the share of the reference supply whose objections send an optimistic proposal to
+    /// a vote
+    ChangeObjectionThreshold(Decimal),
+
+    /// Changes the voting period, the quorum and the supermajority of fast-track proposals
+    ChangeFastTrackRules(u64, Decimal, Decimal),
+
+    /// Changes the voting period of polls
+    ChangePollPeriod(u64),
+
+    /// Changes the rules of the conviction voting: decay, max share of the treasury and weight of
+    /// the threshold
+    ChangeConvictionRules(Decimal, Decimal, Decimal),
+
+    /// Changes whether the support of a proposal is kept or reset when it is amended
+    ChangeAmendmentRule(AmendmentRule),
+
+    /// Changes the support threshold for an amendment to be applied to a proposal
+    ChangeAmendmentThreshold(Decimal),
+
+    /// Changes the period after which an accepted proposal that could not be executed can be
+    /// marked as failed
+    ChangeExecutionGracePeriod(u64)
+}
+
+/// Maximum length in bytes of the title of a proposal
+pub const MAX_TITLE_LENGTH: usize = 100;
+
+/// Maximum length in bytes of the summary of a proposal
+pub const MAX_SUMMARY_LENGTH: usize = 500;
+
+/// Maximum length in bytes of the discussion URL of a proposal
+pub const MAX_URL_LENGTH: usize = 256;
+
+/// Structured metadata of a proposal. The full text of the proposal is stored off ledger and its
+/// hash enables anyone to check that it has not changed since the proposal was made.
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct ProposalMetadata
+{
+    /// Title of the proposal
+    pub title: String,
+
+    /// Short summary of the proposal
+    pub summary: String,
+
+    /// URL of the discussion of the proposal
+    pub discussion_url: String,
+
+    /// Hash of the full document of the proposal
+    pub content_hash: Hash
+}
+
+impl ProposalMetadata
+{
+    /// Panics if a field of the metadata is empty or too long to be stored on ledger
+    pub fn check(&self)
+    {
+        assert!(!self.title.is_empty() && self.title.len() <= MAX_TITLE_LENGTH, "The title should have between 1 and {} bytes", MAX_TITLE_LENGTH);
+        assert!(!self.summary.is_empty() && self.summary.len() <= MAX_SUMMARY_LENGTH, "The summary should have between 1 and {} bytes", MAX_SUMMARY_LENGTH);
+        assert!(!self.discussion_url.is_empty() && self.discussion_url.len() <= MAX_URL_LENGTH, "The discussion URL should have between 1 and {} bytes", MAX_URL_LENGTH);
+    }
+}
+
+/// A version of the metadata and changes of a proposal
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct ProposalVersion
+{
+    /// Title, summary, discussion URL and content hash of the proposal in this version
+    pub metadata: ProposalMetadata,
+
+    /// Changes of the proposal in this version
+    pub changes: Vec<Change>,
+
+    /// Epoch when the version was made
+    pub epoch: u64
+}
+
+/// An amendment to a proposal in suggestion phase suggested by any member. It is applied once it
+/// has enough support
+#[derive(sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe, Clone)]
+pub struct Amendment
+{
+    /// Title, summary, discussion URL and content hash of the proposal after the amendment
+    pub metadata: ProposalMetadata,
+
+    /// Changes of the proposal after the amendment
+    pub changes: Vec<Change>,
+
+    /// Version of the proposal that the amendment revises
+    pub base_version: usize,
+
+    /// Numbers of votes supporting the amendment
+    pub supporting_votes: Decimal,
+
+    /// VoterCards ids of the users who supported the amendment
+    pub supporters: Vec<u64>,
+
+    /// Whether the amendment was applied
+    pub applied: bool
 }
 
 /// Proposal that can be made to the DAO.
@@ -61,8 +267,8 @@ pub struct Proposal
     /// Id of the proposal
     pub id: usize,
 
-    /// Description of the proposal
-    pub description: String,
+    /// Title, summary, discussion URL and content hash of the proposal
+    pub metadata: ProposalMetadata,
 
     /// Changes to be enacted
     pub changes: Vec<Change>,
@@ -82,6 +288,10 @@ pub struct Proposal
     /// Casted blank votes
     pub blank_votes: Decimal,
 
+    /// Voting power of the votes for and against the proposal before the power control function,
+    /// used to check the quorum of fast-track proposals
+    pub raw_votes: Decimal,
+
     /// Number of votes delegated to a ComponentAddress
     pub delegated_votes: HashMap<u64, Decimal>,
 
@@ -91,8 +301,39 @@ pub struct Proposal
     /// Epoch of expiration of the current Proposal period
     pub epoch_expiration: u64,
 
-    /// Amount of tokens emitted at the Proposal creation
-    pub total_emitted_tokens : Decimal
+    /// Reference supply of tokens at the Proposal creation (emitted or circulating tokens)
+    pub reference_supply : Decimal,
+
+    /// VoterCards ids of the veto council members who vetoed the proposal
+    pub vetoed_by: Vec<u64>,
+
+    /// Voting power of the objections to an optimistic proposal
+    pub objections: Decimal,
+
+    /// VoterCards ids of the users who objected to an optimistic proposal
+    pub objected_by: Vec<u64>,
+
+    /// Whether the proposal is a fast-track proposal
+    pub fast_track: bool,
+
+    /// Options and votes of a multiple-choice proposal. The changes of the winning option are
+    /// moved to `changes` when the vote ends
+    pub multiple_choice: Option<MultipleChoice>,
+
+    /// Ids of the proposals that should be executed before this proposal can be executed
+    pub prerequisites: Vec<usize>,
+
+    /// VoterCard id of the author of the proposal, if any
+    pub author: Option<u64>,
+
+    /// VoterCards ids of the users whose support is counted in `supporting_votes`
+    pub supporters: Vec<u64>,
+
+    /// All the versions of the proposal, the last one being the current version
+    pub versions: Vec<ProposalVersion>,
+
+    /// Amendments suggested by the members
+    pub amendments: Vec<Amendment>
 
 }
 
@@ -108,46 +349,43 @@ impl Proposal
     /// * `amount` - amount of voting power delegated
     pub fn add_delegation(&mut self, from: u64, to: u64, amount: Decimal)
     {
+        add_delegation(&mut self.delegation_to, &mut self.delegated_votes, from, to, amount);
+    }
 
-        assert!(self.delegation_to.get(&from).is_none(), "You already delegated to someone else");
-
-        let mut end_of_line_delegator = to;
-        // Look for the last delegator
-        let mut new_link = self.get_delegatee(end_of_line_delegator);
+    /// Makes a new version of the proposal with the given metadata and changes. The support already
+    /// given to the proposal is reset if asked.
+    ///
+    /// # Arguments
+    /// * `metadata` - new title, summary, discussion URL and content hash of the proposal
+    /// * `changes` - new changes of the proposal
+    /// * `reset_support` - whether the support of the proposal should be reset
+    /// * `current_epoch` - current epoch
+    pub fn amend(&mut self, metadata: ProposalMetadata, changes: Vec<Change>, reset_support: bool, current_epoch: u64)
+    {
+        self.changes = changes.clone();
+        self.metadata = metadata.clone();
+        self.versions.push(ProposalVersion { metadata, changes, epoch: current_epoch });
 
-        while new_link != end_of_line_delegator
+        if reset_support
         {
-            end_of_line_delegator = new_link;
-            new_link = self.get_delegatee(end_of_line_delegator);
+            self.supporting_votes = Decimal::zero();
+            self.supporters.clear();
         }
+    }
 
-        if from == end_of_line_delegator
-        {
-            // Delegation loop
-            panic!("Cannot delegate to voter {} because its votes are already delegated to you", to);
-        }
-        else
+    /// Returns all the changes that the proposal could enact, including the changes of every option
+    /// of a multiple-choice proposal
+    pub fn possible_changes(&self) -> Vec<&Change>
+    {
+        let mut changes: Vec<&Change> = self.changes.iter().collect();
+        if let Some(multiple_choice) = &self.multiple_choice
         {
-            let mut number_of_votes = amount;
-
-            match self.delegated_votes.get_mut(&from)
+            for option in multiple_choice.options.iter()
             {
-                None => {}
-                Some(votes) =>
-                    {
-                        number_of_votes = number_of_votes + *votes;
-                        *votes = dec!(0);
-                    }
-            }
-
-            self.delegation_to.insert(from, end_of_line_delegator);
-
-            match self.delegated_votes.get_mut(&end_of_line_delegator)
-            {
-                None => { self.delegated_votes.insert(end_of_line_delegator, number_of_votes); }
-                Some(votes) => { *votes = *votes + number_of_votes; }
+                changes.extend(option.changes.iter());
             }
         }
+        changes
     }
 
     /// Returns the delegatee's id of a voter for the Proposal. If the voter did not delegate
@@ -157,13 +395,92 @@ impl Proposal
     /// * `of` - user VoterCard's id from which to check to whom they are delegating
     pub fn get_delegatee(&self, of: u64) -> u64
     {
-        match self.delegation_to.get(&of)
+        get_delegatee(&self.delegation_to, of)
+    }
+
+}
+
+/// Adds a delegation link between two voters and makes sure that there is no delegation loop by
+/// doing so. It also transfers the delegated votes of the person delegating to the delegatee
+///
+/// # Arguments
+/// * `delegation_to` - to whom each voter has delegated
+/// * `delegated_votes` - votes delegated to each voter
+/// * `from` - user delegating VoterCard's id
+/// * `to` - user delegating to VoterCard's id
+/// * `amount` - amount of voting power delegated
+pub fn add_delegation(delegation_to: &mut HashMap<u64, u64>, delegated_votes: &mut HashMap<u64, Decimal>, from: u64, to: u64, amount: Decimal)
+{
+    assert!(delegation_to.get(&from).is_none(), "You already delegated to someone else");
+
+    let mut end_of_line_delegator = to;
+    // Look for the last delegator
+    let mut new_link = get_delegatee(delegation_to, end_of_line_delegator);
+
+    while new_link != end_of_line_delegator
+    {
+        end_of_line_delegator = new_link;
+        new_link = get_delegatee(delegation_to, end_of_line_delegator);
+    }
+
+    if from == end_of_line_delegator
+    {
+        // Delegation loop
+        panic!("Cannot delegate to voter {} because its votes are already delegated to you", to);
+    }
+    else
+    {
+        let mut number_of_votes = amount;
+
+        match delegated_votes.get_mut(&from)
         {
-            None => of,
-            Some(del) => *del
+            None => {}
+            Some(votes) =>
+                {
+                    number_of_votes = number_of_votes + *votes;
+                    *votes = dec!(0);
+                }
+        }
+
+        delegation_to.insert(from, end_of_line_delegator);
+
+        match delegated_votes.get_mut(&end_of_line_delegator)
+        {
+            None => { delegated_votes.insert(end_of_line_delegator, number_of_votes); }
+            Some(votes) => { *votes = *votes + number_of_votes; }
         }
     }
+}
 
+/// Returns the voter to whom a voter has delegated, or the voter itself if it has not delegated
+///
+/// # Arguments
+/// * `delegation_to` - to whom each voter has delegated
+/// * `of` - VoterCard's id of the voter
+pub fn get_delegatee(delegation_to: &HashMap<u64, u64>, of: u64) -> u64
+{
+    match delegation_to.get(&of)
+    {
+        None => of,
+        Some(del) => *del
+    }
+}
+
+impl Change
+{
+    /// Returns whether the change can be made by a fast-track proposal. Only pauses and new
+    /// outflow limits are eligible, and a new outflow limit should also tighten the current limit
+    /// of the resource, which is checked by the DAO. Changes that loosen a safeguard, such as
+    /// voting thresholds, or act against a member have to go through a full vote
+    pub fn is_fast_track_eligible(&self) -> bool
+    {
+        match self
+        {
+            Change::ChangeOutflowLimit(_, Some(_))
+            | Change::SetPause(_, _) => true,
+            _ => false
+        }
+    }
 }
 
 impl ProposalStatus
@@ -213,13 +530,87 @@ impl ProposalStatus
         }
     }
 
+    pub fn is_veto_phase(&self) -> bool
+    {
+        match self
+        {
+            ProposalStatus::VetoPhase => {true}
+            _ => {false}
+        }
+    }
+
+    pub fn is_vetoed(&self) -> bool
+    {
+        match self
+        {
+            ProposalStatus::Vetoed => {true}
+            _ => {false}
+        }
+    }
+
+    pub fn is_challenge_phase(&self) -> bool
+    {
+        match self
+        {
+            ProposalStatus::ChallengePhase => {true}
+            _ => {false}
+        }
+    }
+
+    pub fn is_execution_failed(&self) -> bool
+    {
+        match self
+        {
+            ProposalStatus::ExecutionFailed => {true}
+            _ => {false}
+        }
+    }
+
+    /// Returns whether the proposal ended without being executed
+    pub fn is_failed(&self) -> bool
+    {
+        self.is_suggestion_rejected() || self.is_proposal_rejected() || self.is_vetoed() || self.is_execution_failed()
+    }
+
 }
 
 #[cfg(test)]
 mod tests
 {
+    use scrypto::crypto::Hash;
     use scrypto::dec;
-    use crate::proposal::{Proposal, ProposalStatus, Change};
+    use crate::proposal::{MAX_TITLE_LENGTH, Proposal, ProposalMetadata, ProposalStatus, Change};
+
+    fn metadata(title: String) -> ProposalMetadata
+    {
+        ProposalMetadata
+        {
+            title,
+            summary: String::from("Summary of the proposal"),
+            discussion_url: String::from("https://forum.example.com/proposal"),
+            content_hash: Hash([0; 32])
+        }
+    }
+
+    #[test]
+    fn test_metadata_check()
+    {
+        metadata("a".repeat(MAX_TITLE_LENGTH)).check();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_metadata_title_too_long()
+    {
+        metadata("a".repeat(MAX_TITLE_LENGTH + 1)).check();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_metadata_empty_title()
+    {
+        metadata(String::new()).check();
+    }
 
     #[test]
     fn test_add_delegation()
@@ -227,17 +618,28 @@ mod tests
         let mut prop = Proposal
         {
             id: 0,
-            description: "".to_string(),
+            metadata: metadata(String::from("Title")),
             changes: vec![Change::ChangeVotePeriod(0)],
             status: ProposalStatus::SuggestionPhase,
             supporting_votes: Default::default(),
             voted_for: Default::default(),
             voted_against: Default::default(),
+            raw_votes: Default::default(),
             blank_votes: Default::default(),
             delegated_votes: Default::default(),
             delegation_to: Default::default(),
             epoch_expiration: 0,
-            total_emitted_tokens: dec!(1)
+            reference_supply: dec!(1),
+            vetoed_by: vec![],
+            objections: Default::default(),
+            objected_by: vec![],
+            fast_track: false,
+            multiple_choice: None,
+            prerequisites: vec![],
+            author: None,
+            supporters: vec![],
+            versions: vec![],
+            amendments: vec![]
         };
 
         prop.add_delegation(0,1, dec!(1000));
@@ -252,17 +654,28 @@ mod tests
         let mut prop = Proposal
         {
             id: 0,
-            description: "".to_string(),
+            metadata: metadata(String::from("Title")),
             changes: vec![Change::ChangeVotePeriod(0)],
             status: ProposalStatus::SuggestionPhase,
             supporting_votes: Default::default(),
             voted_for: Default::default(),
             voted_against: Default::default(),
+            raw_votes: Default::default(),
             blank_votes: Default::default(),
             delegated_votes: Default::default(),
             delegation_to: Default::default(),
             epoch_expiration: 0,
-            total_emitted_tokens: dec!(1)
+            reference_supply: dec!(1),
+            vetoed_by: vec![],
+            objections: Default::default(),
+            objected_by: vec![],
+            fast_track: false,
+            multiple_choice: None,
+            prerequisites: vec![],
+            author: None,
+            supporters: vec![],
+            versions: vec![],
+            amendments: vec![]
         };
 
         prop.add_delegation(0, 1, dec!(1000));
@@ -279,17 +692,28 @@ mod tests
         let mut prop = Proposal
         {
             id: 0,
-            description: "".to_string(),
+            metadata: metadata(String::from("Title")),
             changes: vec![Change::ChangeVotePeriod(0)],
             status: ProposalStatus::SuggestionPhase,
             supporting_votes: Default::default(),
             voted_for: Default::default(),
             voted_against: Default::default(),
+            raw_votes: Default::default(),
             blank_votes: Default::default(),
             delegated_votes: Default::default(),
             delegation_to: Default::default(),
             epoch_expiration: 0,
-            total_emitted_tokens: dec!(1)
+            reference_supply: dec!(1),
+            vetoed_by: vec![],
+            objections: Default::default(),
+            objected_by: vec![],
+            fast_track: false,
+            multiple_choice: None,
+            prerequisites: vec![],
+            author: None,
+            supporters: vec![],
+            versions: vec![],
+            amendments: vec![]
         };
 
         prop.add_delegation(0, 1, dec!(1000));
@@ -306,17 +730,28 @@ mod tests
         let mut prop = Proposal
         {
             id: 0,
-            description: "".to_string(),
+            metadata: metadata(String::from("Title")),
             changes: vec![Change::ChangeVotePeriod(0)],
             status: ProposalStatus::SuggestionPhase,
             supporting_votes: Default::default(),
             voted_for: Default::default(),
             voted_against: Default::default(),
+            raw_votes: Default::default(),
             blank_votes: Default::default(),
             delegated_votes: Default::default(),
             delegation_to: Default::default(),
             epoch_expiration: 0,
-            total_emitted_tokens: dec!(1)
+            reference_supply: dec!(1),
+            vetoed_by: vec![],
+            objections: Default::default(),
+            objected_by: vec![],
+            fast_track: false,
+            multiple_choice: None,
+            prerequisites: vec![],
+            author: None,
+            supporters: vec![],
+            versions: vec![],
+            amendments: vec![]
         };
 
         prop.add_delegation(0, 1, dec!(1000));
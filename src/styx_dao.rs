@@ -1,9 +1,16 @@
 //! Main blueprint with which members of the DAO will interact with
 
 use scrypto::prelude::*;
-use crate::ballot_box::BallotBox;
-use crate::proposal::{Vote, Change};
-use crate::voter_card::VoterCard;
+use crate::ballot_box::{BallotBox, SupplyReference};
+use crate::claim::ClaimableAsset;
+use crate::conviction::ConvictionVoting;
+use crate::dao_resource::{DaoItem, NewResource, ResourceKind};
+use crate::emission::{Emission, EmissionSchedule};
+use crate::multiple_choice::{ProposalOption, VotingMode};
+use crate::outflow::{change_outflow_limit, tightens_outflow_limit, OutflowLimit};
+use crate::pause::{MethodGroup, Pauses};
+use crate::proposal::{ProposalMetadata, ProposalVersion, Vote, Change, ReturnKind};
+use crate::voter_card::{VoterCard, VotingKey};
 
 blueprint! {
     struct Styx {
@@ -14,9 +21,25 @@ blueprint! {
         /// Total of Styx tokens emitted by the DAO
         emitted_tokens: Decimal,
 
+        /// Total of Styx tokens burned
+        burned_tokens: Decimal,
+
+        /// Maximum amount of Styx tokens that can ever be emitted. Burned tokens still count as
+        /// emitted, so burning never makes room for new emissions
+        max_supply: Decimal,
+
+        /// Emission schedule of the Styx tokens, if any
+        emission: Option<Emission>,
+
         /// Vault containing the admin_badge of the DAO
         internal_authority : Vault,
 
+        /// Epoch at which the external admin badge loses its powers, if any
+        external_admin_expiry: Option<u64>,
+
+        /// Whether the powers of the external admin badge were revoked by the DAO
+        external_admin_revoked: bool,
+
         /// Vault containing Styx tokens locked by the users of the DAO
         locker_vault : Vault,
 
@@ -29,14 +52,59 @@ blueprint! {
         /// Id of the next voting card that will be minted
         new_voter_card_id: u64,
 
+        /// Address of the VotingKey NFT
+        voting_key_address: ResourceAddress,
+
+        /// Id of the next voting key that will be minted
+        new_voting_key_id: u64,
+
+        /// Voting keys that have not been revoked with the id of the VoterCard that authorized them
+        active_voting_keys: HashMap<u64, u64>,
+
         /// Ballot Box dealing with votes in the DAO
         ballot_box: BallotBox,
 
         /// Assets owned and managed by the DAO
         assets_under_management: HashMap<ResourceAddress, Vault>,
 
+        /// Resources created by the DAO with their kind
+        created_resources: HashMap<ResourceAddress, ResourceKind>,
+
+        /// Id of the next DaoItem that will be minted
+        new_item_id: u64,
+
         /// Assets that can be claimed by specific members of the DAO
-        claimable_assets: HashMap<u64, HashMap<ResourceAddress, Decimal>>
+        claimable_assets: HashMap<u64, HashMap<ResourceAddress, ClaimableAsset>>,
+
+        /// Vaults containing the assets reserved for the claims
+        claims_escrow: HashMap<ResourceAddress, Vault>,
+
+        /// Number of epochs during which granted assets can be claimed
+        claim_period: u64,
+
+        /// Limits on the outflows of resources decided by proposals
+        outflow_limits: HashMap<ResourceAddress, OutflowLimit>,
+
+        /// Non fungible assets that can be claimed by specific members of the DAO
+        claimable_non_fungibles: HashMap<u64, HashMap<ResourceAddress, Vec<NonFungibleId>>>,
+
+        /// Vaults containing the non fungible assets that can be claimed
+        non_fungible_escrow: HashMap<ResourceAddress, Vault>,
+
+        /// Suspended VoterCards ids with the epoch until which they are suspended
+        suspended_voters: HashMap<u64, u64>,
+
+        /// Address of the badge that is required to mint a VoterCard, if membership is gated
+        membership_badge: Option<ResourceAddress>,
+
+        /// Address of the badge held by the guardians, that can pause some methods of the DAO
+        guardian_badge: Option<ResourceAddress>,
+
+        /// Current pauses of the methods of the DAO
+        pauses: Pauses,
+
+        /// Funding requests decided by conviction voting
+        conviction_voting: ConvictionVoting
     }
 
     impl Styx {
@@ -47,7 +115,8 @@ blueprint! {
         ///
         /// # Arguments
         /// * `initial_supply` - Initial Supply of Styx tokens to put in the styx_vault
-        pub fn instantiate(initial_supply: Decimal) -> (ComponentAddress, Bucket) {
+        /// * `max_supply` - Maximum amount of Styx tokens that can ever be emitted
+        pub fn instantiate(initial_supply: Decimal, max_supply: Decimal) -> (ComponentAddress, Bucket) {
 
 
             // If the DAO is not instantiated with an admin badge, a default one is created
@@ -58,17 +127,28 @@ blueprint! {
             .burnable(rule!(allow_all), LOCKED)
             .initial_supply(dec!(1));
  
-            Self::instantiate_custom(default_admin_badge, initial_supply)
+            Self::instantiate_custom(default_admin_badge, initial_supply, max_supply, None, None, None)
         }
 
 
         /// Instantiates a Styx DAO and returns the address of the blueprint and a Bucket containing the given admin badge.
-        /// will be able to emit new Styx tokens or withdraw some tokens.
+        /// will be able to emit new Styx tokens or withdraw some tokens, until its powers expire or are revoked by the DAO.
         ///
         /// # Arguments
         /// * `initial_supply` - Initial supply of Styx tokens to put in the styx_vault
         /// * `admin_badge` - Admin badge to give permission to mint and withdraw to
-        pub fn instantiate_custom(admin_badge : Bucket, initial_supply: Decimal) -> (ComponentAddress, Bucket) {
+        /// * `max_supply` - Maximum amount of Styx tokens that can ever be emitted
+        /// * `emission_schedule` - Schedule following which Styx tokens are emitted, None for no emission
+        /// * `membership_badge` - Address of the badge required to mint a VoterCard, None for open membership
+        /// * `external_admin_expiry` - Epoch at which the admin badge loses its powers, None for no expiry
+        pub fn instantiate_custom(admin_badge : Bucket,
+                                  initial_supply: Decimal,
+                                  max_supply: Decimal,
+                                  emission_schedule: Option<EmissionSchedule>,
+                                  membership_badge: Option<ResourceAddress>,
+                                  external_admin_expiry: Option<u64>) -> (ComponentAddress, Bucket) {
+
+            assert!(initial_supply <= max_supply, "The initial supply cannot be greater than the max supply");
 
             // Creates the admin badge owned by the DAO contract
             let internal_admin: Bucket = ResourceBuilder::new_fungible()
@@ -99,6 +179,10 @@ blueprint! {
                     rule!( require(internal_admin.resource_address())),
                     MUTABLE(internal_access.clone())
                 )
+                .burnable(
+                    rule!( require(internal_admin.resource_address())),
+                    MUTABLE(internal_access.clone())
+                )
                 .initial_supply(initial_supply);
 
             let styx_address: ResourceAddress = styx_bucket.resource_address();
@@ -106,35 +190,65 @@ blueprint! {
             // Creation of the address of voter cards NFT
             let voter_card_address = ResourceBuilder::new_non_fungible()
                 .metadata("name","VoterCard")
+                .updateable_metadata(internal_access.clone(), LOCKED)
                 .mintable(internal_access.clone(), LOCKED)
                 .burnable(internal_access.clone(), LOCKED)
                 .restrict_withdraw(internal_access.clone(), MUTABLE(internal_access.clone()))
                 .updateable_non_fungible_data(internal_access.clone(), LOCKED)
                 .no_initial_supply();
 
+            // Creation of the address of voting keys NFT
+            let voting_key_address = ResourceBuilder::new_non_fungible()
+                .metadata("name","VotingKey")
+                .mintable(internal_access.clone(), LOCKED)
+                .burnable(internal_access.clone(), LOCKED)
+                .no_initial_supply();
+
 
             let styx_dao = Self {
                 styx_vault: Vault::with_bucket(styx_bucket),
                 internal_authority: Vault::with_bucket(internal_admin),
+                external_admin_expiry,
+                external_admin_revoked: false,
                 voter_card_address : voter_card_address,
                 locker_vault : Vault::new(styx_address),
                 styx_address,
                 ballot_box: BallotBox::new(),
                 new_voter_card_id: 0,
+                voting_key_address,
+                new_voting_key_id: 0,
+                active_voting_keys: HashMap::new(),
                 emitted_tokens: initial_supply,
+                burned_tokens: Decimal::zero(),
+                max_supply,
+                emission: emission_schedule.map(|schedule| Emission::new(schedule, Runtime::current_epoch())),
                 assets_under_management: HashMap::new(),
-                claimable_assets: HashMap::new()
+                created_resources: HashMap::new(),
+                new_item_id: 0,
+                claimable_assets: HashMap::new(),
+                claims_escrow: HashMap::new(),
+                claim_period: 8760,
+                outflow_limits: HashMap::new(),
+                claimable_non_fungibles: HashMap::new(),
+                non_fungible_escrow: HashMap::new(),
+                suspended_voters: HashMap::new(),
+                membership_badge,
+                guardian_badge: None,
+                pauses: Pauses::new(168),
+                conviction_voting: ConvictionVoting::new(dec!("0.99"), dec!("0.2"), dec!("0.0025"))
             };
 
 
             info!(" external_admin : {},
                     internal_admin : {},
                     styx : {},
-                    voter_card : {}",
+                    voter_card : {},
+                    voting_key : {}",
                     admin_badge.resource_address(),
                     styx_dao.internal_authority.resource_address(),
                     styx_dao.styx_address,
-                    styx_dao.voter_card_address
+                    styx_dao.voter_card_address,
+                    styx_dao.voting_key_address
                 );
             
             let mut dao = styx_dao.instantiate();
@@ -143,12 +257,15 @@ blueprint! {
             return (dao.globalize(),admin_badge)
         }
 
-        /// Mints a new voter card with initial locked tokens given as deposit and returns it
+        /// Mints a new voter card with initial locked tokens given as deposit and returns it.
+        /// If membership is gated, a proof of the membership badge has to be provided.
         ///
         /// # Arguments
         /// * `deposit` - Bucket containing some Styx tokens to deposit
-        pub fn mint_voter_card_with_bucket(&mut self, deposit : Bucket) -> Bucket {
+        /// * `membership_proof` - proof of the membership badge
+        pub fn mint_voter_card_with_bucket(&mut self, deposit : Bucket, membership_proof: Option<Proof>) -> Bucket {
             assert_eq!(deposit.resource_address(), self.styx_address);
+            self.check_membership(membership_proof);
 
             info!("You are going to lock : {}", deposit.amount());
             let mut voter_card = VoterCard::new(self.new_voter_card_id);
@@ -175,27 +292,135 @@ blueprint! {
         /// * `amount` - amount of Styx tokens to emit
         ///
         /// # Access Rule
-        /// Can only be called by this blueprint or the owner of the DAO
+        /// Can only be called by this blueprint or the owner of the DAO. The owner of the DAO can
+        /// only call it as long as its powers did not expire and were not revoked. Proposals cannot
+        /// call the methods of the DAO, so the internal badge is never used to bypass these checks
         pub fn withdraw(&mut self, amount: Decimal) -> Bucket
         {
+            assert!(self.external_admin_active(), "The external admin badge has no power anymore");
             assert!(amount <= self.styx_vault.amount());
             self.styx_vault.take(amount)
         }
 
-        /// Emits a certain amount of Styx tokens and deposit them in the styx_vault
+        /// Emits a certain amount of Styx tokens and deposit them in the styx_vault.
+        /// The total of emitted tokens can never go over the max supply.
         ///
         /// # Arguments
         /// * `amount` - amount of Styx tokens to emit
         ///
         /// # Access Rule
-        /// Can only be called by this blueprint or the owner of the DAO
+        /// Can only be called by this blueprint or the owner of the DAO. The owner of the DAO can
+        /// only call it as long as its powers did not expire and were not revoked. Proposals cannot
+        /// call the methods of the DAO, so the internal badge is never used to bypass these checks
         pub fn emit(&mut self, amount: Decimal)
         {
-            let bucket = self.internal_authority.authorize(|| {
-                borrow_resource_manager!(self.styx_address).mint(amount)
-            });
-            self.emitted_tokens = self.emitted_tokens + amount;
-            self.styx_vault.put(bucket);
+            assert!(self.external_admin_active(), "The external admin badge has no power anymore");
+            self.mint_styx(amount);
+        }
+
+        /// Returns whether the external admin badge can still withdraw and emit Styx tokens
+        pub fn external_admin_active(&self) -> bool
+        {
+            if self.external_admin_revoked
+            {
+                return false;
+            }
+
+            match self.external_admin_expiry
+            {
+                None => true,
+                Some(expiry) => Runtime::current_epoch() < expiry
+            }
+        }
+
+        /// Emits the Styx tokens due according to the emission schedule and deposits them in the
+        /// styx_vault. Can be called by anyone.
+        pub fn trigger_emission(&mut self)
+        {
+            let amount = match &mut self.emission
+            {
+                None => { panic!("There is no emission schedule"); }
+                Some(emission) => emission.emit(self.emitted_tokens, self.max_supply, Runtime::current_epoch())
+            };
+
+            if !amount.is_zero()
+            {
+                self.mint_styx(amount);
+            }
+        }
+
+        /// Returns the assets of the expired claims to the treasury. Can be called by anyone.
+        pub fn sweep_expired_claims(&mut self)
+        {
+            let current_epoch = Runtime::current_epoch();
+            let mut expired: Vec<(ResourceAddress, Decimal)> = vec![];
+
+            for hashmap in self.claimable_assets.values_mut()
+            {
+                for (resource, claimable_asset) in hashmap.iter_mut()
+                {
+                    let expired_amount = claimable_asset.remove_expired(current_epoch);
+                    if expired_amount.is_positive()
+                    {
+                        expired.push((*resource, expired_amount));
+                    }
+                }
+                hashmap.retain(|_, claimable_asset| !claimable_asset.is_empty());
+            }
+            self.claimable_assets.retain(|_, hashmap| !hashmap.is_empty());
+
+            for (resource, amount) in expired.into_iter()
+            {
+                let bucket = self.claims_escrow.get_mut(&resource).unwrap().take(amount);
+                self.gift_asset(bucket);
+            }
+        }
+
+        /// Pauses a group of methods of the DAO for a given number of epochs. Can only be called by
+        /// a guardian and the pause cannot be longer than the max guardian pause.
+        ///
+        /// # Arguments
+        /// * `guardian_proof` - proof of the guardian badge
+        /// * `group` - group of methods to pause
+        /// * `duration` - number of epochs of the pause
+        pub fn pause(&mut self, guardian_proof: Proof, group: MethodGroup, duration: u64)
+        {
+            let guardian_badge = self.guardian_badge.expect("There are no guardians");
+            guardian_proof
+                .validate_proof(ProofValidationMode::ValidateResourceAddress(guardian_badge))
+                .expect("Invalid guardian proof provided");
+
+            self.pauses.guardian_pause(group, duration, Runtime::current_epoch());
+        }
+
+        /// Burns the given Styx tokens. The burned tokens leave the circulating supply but still
+        /// count towards the max supply.
+        ///
+        /// # Arguments
+        /// * `tokens` - Bucket containing the Styx tokens to burn
+        pub fn burn(&mut self, tokens: Bucket)
+        {
+            assert_eq!(tokens.resource_address(), self.styx_address);
+            self.burned_tokens = self.burned_tokens + tokens.amount();
+            self.internal_authority.authorize(|| tokens.burn());
+        }
+
+        /// Returns the amount of Styx tokens in circulation: emitted tokens that were neither burned
+        /// nor are owned by the DAO, including the tokens reserved for claims that were not claimed yet
+        pub fn circulating_supply(&self) -> Decimal
+        {
+            let escrowed = match self.claims_escrow.get(&self.styx_address)
+            {
+                None => Decimal::zero(),
+                Some(vault) => vault.amount()
+            };
+            self.emitted_tokens - self.burned_tokens - self.styx_vault.amount() - escrowed
+        }
+
+        /// Returns the maximum amount of Styx tokens that can ever be emitted
+        pub fn max_supply(&self) -> Decimal
+        {
+            self.max_supply
         }
 
         /// Locks the deposited amount of Styx tokens and updates the VoterCard associated with the proof
@@ -213,7 +438,7 @@ blueprint! {
             voter_card.add_tokens(amount, Runtime::current_epoch());
             self.change_data(&validated_proof, voter_card);
 
-            self.styx_vault.put(deposit);
+            self.locker_vault.put(deposit);
 
         }
         /// Unlocks the given amount of Styx tokens and updates the VoterCard associated with the proof
@@ -223,12 +448,14 @@ blueprint! {
         /// * `deposit` - amount of tokens to unlock
         pub fn unlock(&mut self, proof : Proof, amount: Decimal) -> Bucket
         {
+            assert!(!self.is_paused(MethodGroup::Unlocks), "Unlocks are paused");
 
             let validated_proof = self.check_proof(proof);
             let mut voter_card : VoterCard = self.get_voter_card_data_from_proof(&validated_proof);
             assert!(voter_card.total_number_of_token >= amount);
 
             voter_card.retrieve_tokens(amount);
+            self.conviction_voting.withdraw_all_stakes(voter_card.voter_id, Runtime::current_epoch());
 
             self.change_data(&validated_proof, voter_card);
             self.locker_vault.take(amount)
@@ -240,27 +467,158 @@ blueprint! {
         /// * `voter_card_proof` - Proof of the user's VoterCard
         pub fn unlock_all(&mut self, proof : Proof) -> Bucket
         {
+            assert!(!self.is_paused(MethodGroup::Unlocks), "Unlocks are paused");
             let validated_proof = self.check_proof(proof);
 
             let mut voter_card : VoterCard = self.get_voter_card_data_from_proof(&validated_proof);
 
             let total_number_of_token = voter_card.retrieve_all_tokens();
+            self.conviction_voting.withdraw_all_stakes(voter_card.voter_id, Runtime::current_epoch());
 
             self.change_data(&validated_proof, voter_card);
             self.locker_vault.take(total_number_of_token)
         }
 
-        /// Make a new Proposal to the Styx DAO. The Proposal then enters the Suggestion phase
+        /// Make a new Proposal to the Styx DAO. The Proposal then enters the Suggestion phase,
+        /// unless it is a fast-track Proposal which directly enters a short Voting phase.
+        /// The Proposal cannot be executed before its prerequisites and is rejected if one of them fails.
+        ///
+        /// # Arguments
+        /// * `metadata` - title, summary, discussion URL and content hash of the Proposal
+        /// * `suggested_changes` - list of changes to be made to the DAO
+        /// * `fast_track` - whether the Proposal is a fast-track Proposal
+        /// * `prerequisites` - ids of the Proposals that should be executed before this Proposal
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn make_proposal(&mut self, metadata: ProposalMetadata, suggested_changes: Vec<Change>, fast_track: bool, prerequisites: Vec<usize>, voter_card_proof: Proof)
+        {
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+            self.check_changes(&suggested_changes);
+
+            let proposal_id = self.ballot_box.new_proposal_id();
+            let reference_supply = self.reference_supply();
+            if fast_track
+            {
+                self.check_fast_track_limits(&self.outflow_limits, &suggested_changes)
+                    .expect("Fast-track proposals can only tighten outflow limits");
+                self.ballot_box.make_fast_track_proposal(metadata, suggested_changes, Runtime::current_epoch(), reference_supply);
+            }
+            else
+            {
+                self.ballot_box.make_proposal(metadata, suggested_changes, Runtime::current_epoch(), reference_supply);
+            }
+            self.ballot_box.set_prerequisites(proposal_id, prerequisites);
+            self.ballot_box.set_author(proposal_id, voter_card.voter_id);
+        }
+
+        /// Amends a Proposal in Suggestion phase. Can only be called by the author of the Proposal.
+        ///
+        /// # Arguments
+        /// * `proposal_id` - id of the Proposal
+        /// * `metadata` - new title, summary, discussion URL and content hash of the Proposal
+        /// * `changes` - new list of changes of the Proposal
+        /// * `voter_card_proof` - proof of the author's VoterCard
+        pub fn amend_proposal(&mut self, proposal_id: usize, metadata: ProposalMetadata, changes: Vec<Change>, voter_card_proof: Proof)
+        {
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+            self.check_changes(&changes);
+            self.ballot_box.amend_proposal(proposal_id, voter_card.voter_id, metadata, changes, Runtime::current_epoch());
+        }
+
+        /// Suggests an amendment to a Proposal in Suggestion phase. The amendment is applied once it
+        /// has enough support.
+        ///
+        /// # Arguments
+        /// * `proposal_id` - id of the Proposal
+        /// * `metadata` - new title, summary, discussion URL and content hash of the Proposal
+        /// * `changes` - new list of changes of the Proposal
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn suggest_amendment(&mut self, proposal_id: usize, metadata: ProposalMetadata, changes: Vec<Change>, voter_card_proof: Proof)
+        {
+            self.check_proof(voter_card_proof);
+            self.check_changes(&changes);
+            self.ballot_box.suggest_amendment(proposal_id, metadata, changes, Runtime::current_epoch());
+        }
+
+        /// Supports an amendment to a Proposal in Suggestion phase
+        ///
+        /// # Arguments
+        /// * `proposal_id` - id of the Proposal
+        /// * `amendment_id` - id of the amendment
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn support_amendment(&mut self, proposal_id: usize, amendment_id: usize, voter_card_proof: Proof)
+        {
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+            self.ballot_box.support_amendment(proposal_id, amendment_id, &voter_card, Runtime::current_epoch());
+        }
+
+        /// Returns the title, summary, discussion URL and content hash of a Proposal
+        ///
+        /// # Arguments
+        /// * `proposal_id` - id of the Proposal
+        pub fn proposal_metadata(&self, proposal_id: usize) -> ProposalMetadata
+        {
+            self.ballot_box.get_proposal(proposal_id).metadata.clone()
+        }
+
+        /// Returns all the versions of a Proposal, the last one being the current version
+        ///
+        /// # Arguments
+        /// * `proposal_id` - id of the Proposal
+        pub fn proposal_versions(&self, proposal_id: usize) -> Vec<ProposalVersion>
+        {
+            self.ballot_box.get_proposal(proposal_id).versions.clone()
+        }
+
+        /// Make a new multiple-choice Proposal to the Styx DAO. The Proposal then enters the
+        /// Suggestion phase and only the changes of the winning option are executed.
+        ///
+        /// # Arguments
+        /// * `metadata` - title, summary, discussion URL and content hash of the Proposal
+        /// * `options` - options to choose from, with their changes
+        /// * `mode` - voting mode of the Proposal
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn make_multiple_choice_proposal(&mut self, metadata: ProposalMetadata, options: Vec<ProposalOption>, mode: VotingMode, voter_card_proof: Proof)
+        {
+            self.check_proof(voter_card_proof);
+            for option in options.iter()
+            {
+                self.check_changes(&option.changes);
+            }
+
+            let reference_supply = self.reference_supply();
+            self.ballot_box.make_multiple_choice_proposal(metadata, options, mode, Runtime::current_epoch(), reference_supply);
+        }
+
+        /// Make a new optimistic Proposal to the Styx DAO. The Proposal can only spend amounts
+        /// under the optimistic limits and is accepted at the end of the challenge period unless
+        /// enough voting power objects to it.
         ///
         /// # Arguments
-        /// * `description` - description of the Proposal
+        /// * `metadata` - title, summary, discussion URL and content hash of the Proposal
         /// * `suggested_changes` - list of changes to be made to the DAO
         /// * `voter_card_proof` - proof of the user's VoterCard
-        pub fn make_proposal(&mut self, description: String, suggested_changes: Vec<Change>, voter_card_proof: Proof)
+        pub fn make_optimistic_proposal(&mut self, metadata: ProposalMetadata, suggested_changes: Vec<Change>, voter_card_proof: Proof)
         {
-            // Check that it is a user of the DAO
             self.check_proof(voter_card_proof);
-            self.ballot_box.make_proposal(description, suggested_changes, Runtime::current_epoch(), self.emitted_tokens);
+            self.check_changes(&suggested_changes);
+
+            let reference_supply = self.reference_supply();
+            self.ballot_box.make_optimistic_proposal(metadata, suggested_changes, Runtime::current_epoch(), reference_supply);
+        }
+
+        /// Objects to an optimistic Proposal that is in Challenge phase
+        ///
+        /// # Arguments
+        /// * `proposal_id` - id of the Proposal to object to
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn object_to_proposal(&mut self, proposal_id: usize, voter_card_proof: Proof)
+        {
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+            self.ballot_box.object_to_proposal(proposal_id, &voter_card, Runtime::current_epoch());
         }
 
         /// Support a given Proposal that is in Suggestion phase
@@ -277,39 +635,139 @@ blueprint! {
             self.change_data(&validated_id, voter_card);
         }
 
+        /// Mints a new VotingKey for the VoterCard associated with the proof and returns it. The
+        /// VotingKey can support and vote for proposals with the voting power of the VoterCard until
+        /// it is revoked.
+        ///
+        /// # Arguments
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn create_voting_key(&mut self, voter_card_proof: Proof) -> Bucket
+        {
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+
+            let voting_key = VotingKey
+            {
+                key_id: self.new_voting_key_id,
+                voter_id: voter_card.voter_id
+            };
+
+            let voting_key_bucket = self.internal_authority.authorize(|| {
+                borrow_resource_manager!(self.voting_key_address).mint_non_fungible(
+                    &NonFungibleId::from_u64(self.new_voting_key_id),
+                    voting_key
+                )
+            });
+            self.active_voting_keys.insert(self.new_voting_key_id, voter_card.voter_id);
+            self.new_voting_key_id += 1;
+
+            voting_key_bucket
+        }
+
+        /// Revokes a VotingKey authorized by the VoterCard associated with the proof
+        ///
+        /// # Arguments
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        /// * `key_id` - id of the VotingKey to revoke
+        pub fn revoke_voting_key(&mut self, voter_card_proof: Proof, key_id: u64)
+        {
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+
+            match self.active_voting_keys.get(&key_id)
+            {
+                Some(voter_id) if *voter_id == voter_card.voter_id =>
+                    {
+                        self.active_voting_keys.remove(&key_id);
+                    }
+                _ => { panic!("This VotingKey is not active for your VoterCard"); }
+            }
+        }
+
+        /// Support a given Proposal that is in Suggestion phase with a VotingKey
+        ///
+        /// # Arguments
+        /// * `proposal_id` - id of the Proposal to support
+        /// * `voting_key_proof` - proof of the user's VotingKey
+        pub fn support_proposal_with_key(&mut self, proposal_id: usize, voting_key_proof: Proof)
+        {
+            let voter_card_id = self.check_voting_key_proof(voting_key_proof);
+            let mut voter_card = self.get_voter_card_data_from_id(&voter_card_id);
+
+            self.ballot_box.support_proposal(proposal_id, &mut voter_card, Runtime::current_epoch());
+            self.change_data_from_id(&voter_card_id, voter_card);
+        }
+
         /// Tries to make a Proposal advance to its next phase and executes the changes if the Proposal
-        /// is accepted
+        /// is accepted. If the DAO does not own enough assets to make all the changes or if they go over
+        /// an outflow limit or the max supply, none of them are made and the execution of the
+        /// Proposal fails.
         ///
         /// # Arguments
         /// * `proposal_id` - id of the Proposal
         pub fn advance_with_proposal(&mut self, proposal_id: usize)
         {
-            match self.ballot_box.advance_with_proposal(proposal_id, Runtime::current_epoch())
+            if self.is_paused(MethodGroup::ProposalExecution)
+            {
+                let proposal = self.ballot_box.get_proposal(proposal_id);
+                let only_pause_changes = proposal.possible_changes().iter().all(|change| matches!(change,
+                    Change::ChangeGuardianBadge(_) | Change::ChangeMaxGuardianPause(_) | Change::SetPause(_, _)));
+                let may_execute = proposal.status.is_voting_phase() || proposal.status.is_veto_phase() || proposal.status.is_challenge_phase();
+                assert!(!may_execute || only_pause_changes, "The execution of proposals is paused");
+            }
+
+            match self.ballot_box.advance_proposal(proposal_id, Runtime::current_epoch())
             {
-                // the BallotBox passes changes that are made to this blueprints
                 None => {}
                 Some(changes) =>
                 {
-                    for change in changes
+                    // A proposal that cannot be executed as a whole fails instead of staying stuck
+                    let fast_track = self.ballot_box.get_proposal(proposal_id).fast_track;
+                    match self.check_execution(&changes, fast_track)
                     {
-                        match change
-                        {
-                            Change::AllowSpending(address, amount, to) =>
-                                {
-                                    self.allow_spending(address, amount, to);
-                                }
-
-                            Change::AllowMinting(amount) =>
+                        Err(reason) =>
+                            {
+                                info!("The execution of the proposal failed: {}", reason);
+                                self.ballot_box.fail_execution(proposal_id);
+                            }
+                        Ok(()) =>
+                            {
+                                // the BallotBox passes changes that are made to this blueprints
+                                for change in self.ballot_box.execute_proposal(&changes).unwrap_or(vec![])
                                 {
-                                    self.emit(amount);
+                                    self.execute_change(change);
                                 }
-                            _ => { panic!("critical error in code. This should not happen.") }
-                        }
+                            }
                     }
                 }
             }
         }
 
+        /// Marks as failed an accepted Proposal whose execution keeps reverting, for example because
+        /// a called method panics, once the execution grace period has passed. None of its changes
+        /// are made and the Proposals depending on it are rejected. Can be called by anyone.
+        ///
+        /// # Arguments
+        /// * `proposal_id` - id of the Proposal
+        pub fn fail_stuck_proposal(&mut self, proposal_id: usize)
+        {
+            assert!(!self.is_paused(MethodGroup::ProposalExecution), "The execution of proposals is paused");
+            self.ballot_box.fail_stuck_proposal(proposal_id, Runtime::current_epoch());
+        }
+
+        /// Vetoes an accepted Proposal during its veto phase. Can only be called by a member of
+        /// the veto council.
+        ///
+        /// # Arguments
+        /// * `proposal_id` - id of the Proposal
+        /// * `voter_card_proof` - proof of the council member's VoterCard
+        pub fn veto_proposal(&mut self, proposal_id: usize, voter_card_proof: Proof)
+        {
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+            self.ballot_box.veto_proposal(proposal_id, voter_card.voter_id, Runtime::current_epoch());
+        }
+
         /// Delegates locked tokens to a given user for a Proposal that is in Voting phase
         ///
         /// # Arguments
@@ -319,6 +777,7 @@ blueprint! {
         pub fn delegate_for_proposal(&mut self, proposal_id: usize, delegate_to: u64, voter_card_proof: Proof)
         {
             let validated_id = self.check_proof(voter_card_proof);
+            assert!(!self.is_suspended(delegate_to), "Cannot delegate to a suspended voter");
             let mut voter_card = self.get_voter_card_data_from_proof(&validated_id);
 
             self.ballot_box.delegate_for_proposal(proposal_id, delegate_to, &mut voter_card, Runtime::current_epoch());
@@ -340,45 +799,169 @@ blueprint! {
             self.change_data(&validated_id, voter_card);
         }
 
-        /// Gifts an asset to the DAO and puts it in the assets_under_management
+        /// Votes with locked and delegated tokens for a Proposal that is in Voting Phase with a VotingKey
         ///
         /// # Arguments
-        /// * `asset` - Bucket containing the asset to gift to the DAO
-        pub fn gift_asset(&mut self, asset: Bucket)
+        /// * `proposal_id` - id of the Proposal
+        /// * `voting_key_proof` - proof of the user's VotingKey
+        /// * `vote` - vote to cast
+        pub fn vote_for_proposal_with_key(&mut self, proposal_id: usize, voting_key_proof: Proof, vote: Vote)
         {
-            let asset_address = asset.resource_address();
-            if asset_address == self.styx_address
-            {
-                self.styx_vault.put(asset)
-            }
-            else
-            {
-                match self.assets_under_management.get_mut(&asset_address)
-                {
-                    None =>
-                        {
-                            let mut  vault = Vault::new(asset_address);
-                            vault.put(asset);
-                            self.assets_under_management.insert(asset_address, vault);
-                        }
-                    Some(vault) =>
-                        {
-                            vault.put(asset);
-                        }
-                }
-            }
+            let voter_card_id = self.check_voting_key_proof(voting_key_proof);
+            let mut voter_card = self.get_voter_card_data_from_id(&voter_card_id);
+
+            self.ballot_box.vote_for_proposal(proposal_id, &mut voter_card, vote, Runtime::current_epoch());
+            self.change_data_from_id(&voter_card_id, voter_card);
         }
 
+        /// Makes a new funding request for the user. The request is funded through conviction voting
+        /// instead of a Proposal and the requested amount becomes claimable by the user once the
+        /// conviction of the request passes its threshold.
+        ///
+        /// # Arguments
+        /// * `description` - description of the request
+        /// * `resource` - address of the requested resource
+        /// * `amount` - requested amount
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn make_funding_request(&mut self, description: String, resource: ResourceAddress, amount: Decimal, voter_card_proof: Proof)
+        {
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+            self.conviction_voting.make_request(description, resource, amount, voter_card.voter_id, Runtime::current_epoch());
+        }
 
-        /// Returns the amount owned by the DAO in a given asset
+        /// Stakes voting power on a funding request and executes the request if its conviction
+        /// passed the threshold. Stakes are withdrawn when the user unlocks tokens.
         ///
         /// # Arguments
-        /// * `asset_address` - address of the asset to check
-        pub fn amount_owned(&self, asset_address: ResourceAddress) -> Decimal
+        /// * `request_id` - id of the funding request
+        /// * `amount` - voting power to stake
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn stake_on_funding_request(&mut self, request_id: usize, amount: Decimal, voter_card_proof: Proof)
         {
-            if asset_address == self.styx_address
-            {
-                self.styx_vault.amount()
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+            let current_epoch = Runtime::current_epoch();
+
+            self.conviction_voting.stake(request_id, voter_card.voter_id, voter_card.voting_power(current_epoch), amount, current_epoch);
+            self.try_execute_funding_request(request_id);
+        }
+
+        /// Removes voting power staked on a funding request
+        ///
+        /// # Arguments
+        /// * `request_id` - id of the funding request
+        /// * `amount` - voting power to remove
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn unstake_from_funding_request(&mut self, request_id: usize, amount: Decimal, voter_card_proof: Proof)
+        {
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+            self.conviction_voting.unstake(request_id, voter_card.voter_id, amount, Runtime::current_epoch());
+        }
+
+        /// Updates the conviction of a funding request and executes it if its conviction passed the
+        /// threshold. Can be called by anyone.
+        ///
+        /// # Arguments
+        /// * `request_id` - id of the funding request
+        pub fn update_funding_request(&mut self, request_id: usize)
+        {
+            self.try_execute_funding_request(request_id);
+        }
+
+        /// Make a new non-binding Poll. The Poll can directly be voted on and is never executed.
+        ///
+        /// # Arguments
+        /// * `description` - question asked by the Poll
+        /// * `options` - options to choose from
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn make_poll(&mut self, description: String, options: Vec<String>, voter_card_proof: Proof)
+        {
+            self.check_proof(voter_card_proof);
+
+            let reference_supply = self.reference_supply();
+            self.ballot_box.make_poll(description, options, Runtime::current_epoch(), reference_supply);
+        }
+
+        /// Votes with locked and delegated tokens for an option of a Poll
+        ///
+        /// # Arguments
+        /// * `poll_id` - id of the Poll
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        /// * `option` - index of the chosen option
+        pub fn vote_for_poll(&mut self, poll_id: usize, voter_card_proof: Proof, option: usize)
+        {
+            let validated_id = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_id);
+
+            self.ballot_box.vote_for_poll(poll_id, &voter_card, option, Runtime::current_epoch());
+        }
+
+        /// Delegates locked tokens to a given user for a Poll
+        ///
+        /// # Arguments
+        /// * `poll_id` - id of the Poll
+        /// * `delegate_to` - user's VoterCard id to whom to delegate
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        pub fn delegate_for_poll(&mut self, poll_id: usize, delegate_to: u64, voter_card_proof: Proof)
+        {
+            let validated_id = self.check_proof(voter_card_proof);
+            assert!(!self.is_suspended(delegate_to), "Cannot delegate to a suspended voter");
+            let voter_card = self.get_voter_card_data_from_proof(&validated_id);
+
+            self.ballot_box.delegate_for_poll(poll_id, delegate_to, &voter_card, Runtime::current_epoch());
+        }
+
+        /// Returns the options of a Poll with the votes cast for each of them
+        ///
+        /// # Arguments
+        /// * `poll_id` - id of the Poll
+        pub fn poll_results(&self, poll_id: usize) -> Vec<(String, Decimal)>
+        {
+            let poll = self.ballot_box.get_poll(poll_id);
+            poll.options.iter().cloned().zip(poll.tallies.iter().cloned()).collect()
+        }
+
+        /// Gifts an asset to the DAO and puts it in the assets_under_management
+        ///
+        /// # Arguments
+        /// * `asset` - Bucket containing the asset to gift to the DAO
+        pub fn gift_asset(&mut self, asset: Bucket)
+        {
+            let asset_address = asset.resource_address();
+            if asset_address == self.styx_address
+            {
+                self.styx_vault.put(asset)
+            }
+            else
+            {
+                match self.assets_under_management.get_mut(&asset_address)
+                {
+                    None =>
+                        {
+                            let mut  vault = Vault::new(asset_address);
+                            vault.put(asset);
+                            self.assets_under_management.insert(asset_address, vault);
+                        }
+                    Some(vault) =>
+                        {
+                            vault.put(asset);
+                        }
+                }
+            }
+        }
+
+
+        /// Returns the amount owned by the DAO in a given asset
+        ///
+        /// # Arguments
+        /// * `asset_address` - address of the asset to check
+        pub fn amount_owned(&self, asset_address: ResourceAddress) -> Decimal
+        {
+            if asset_address == self.styx_address
+            {
+                self.styx_vault.amount()
             }
             else
             {
@@ -390,6 +973,33 @@ blueprint! {
             }
         }
 
+        /// Returns the ids of the non fungible assets of a given resource owned by the DAO and that
+        /// are not claimable by a member. Returns an empty list for fungible resources.
+        ///
+        /// # Arguments
+        /// * `asset_address` - address of the non fungible resource to check
+        pub fn non_fungible_ids_owned(&self, asset_address: ResourceAddress) -> Vec<NonFungibleId>
+        {
+            match self.assets_under_management.get(&asset_address)
+            {
+                Some(vault) if matches!(borrow_resource_manager!(asset_address).resource_type(), ResourceType::NonFungible) =>
+                    {
+                        vault.non_fungible_ids().into_iter().collect()
+                    }
+                _ => vec![]
+            }
+        }
+
+        /// Returns the amount of a resource that proposals can still spend or mint during the current
+        /// window, or None if the resource has no outflow limit
+        ///
+        /// # Arguments
+        /// * `asset_address` - address of the resource to check
+        pub fn outflow_available(&self, asset_address: ResourceAddress) -> Option<Decimal>
+        {
+            self.outflow_limits.get(&asset_address).map(|limit| limit.available(Runtime::current_epoch()))
+        }
+
         /// Returns the amount of DAO tokens locked
         pub fn amount_locked(&self) -> Decimal
         {
@@ -397,92 +1007,420 @@ blueprint! {
         }
 
 
+        /// Returns the amount of a given asset that a user can claim
+        ///
+        /// # Arguments
+        /// * `voter_id` - id of the user's VoterCard
+        /// * `asset_address` - address of the asset to check
+        pub fn claimable_amount(&self, voter_id: u64, asset_address: ResourceAddress) -> Decimal
+        {
+            match self.claimable_assets.get(&voter_id).and_then(|hashmap| hashmap.get(&asset_address))
+            {
+                None => Decimal::zero(),
+                Some(claimable_asset) => claimable_asset.claimable(Runtime::current_epoch())
+            }
+        }
+
         /// Claims the assets due to a user and returns them as a list of buckets
         ///
         /// # Arguments
         /// * `voter_card_proof` - proof of the user's VoterCard
         pub fn claim_assets(&mut self, voter_card_proof: Proof) -> Vec<Bucket>
         {
+            assert!(!self.is_paused(MethodGroup::Claims), "Claims are paused");
             let validated_proof = self.check_proof(voter_card_proof);
             let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
 
             let mut buckets: Vec<Bucket> = vec![];
 
-            match self.claimable_assets.get_mut(&voter_card.voter_id)
+            let resources: Vec<ResourceAddress> = match self.claimable_assets.get(&voter_card.voter_id)
+            {
+                None => vec![],
+                Some(hashmap) => hashmap.keys().cloned().collect()
+            };
+
+            for resource in resources.into_iter()
+            {
+                if self.claimable_amount(voter_card.voter_id, resource).is_positive()
+                {
+                    buckets.push(self.take_claimed_asset(voter_card.voter_id, resource, None));
+                }
+            }
+
+            match self.claimable_non_fungibles.remove(&voter_card.voter_id)
             {
                 None => {}
                 Some(hashmap) =>
                     {
+                        for (resource, ids) in hashmap.into_iter()
+                        {
+                            let escrow = self.non_fungible_escrow.get_mut(&resource).unwrap();
+                            let mut new_bucket = Bucket::new(resource);
+                            for id in ids.iter()
+                            {
+                                new_bucket.put(escrow.take_non_fungible(id));
+                            }
+                            buckets.push(new_bucket);
+                        }
+                    }
+            }
+
+            buckets
+        }
+
+        /// Claims a given asset due to a user. If no amount is given, claims everything that can
+        /// be claimed for this asset.
+        ///
+        /// # Arguments
+        /// * `voter_card_proof` - proof of the user's VoterCard
+        /// * `asset_address` - address of the asset to claim
+        /// * `amount` - optional amount of the asset to claim
+        pub fn claim_asset(&mut self, voter_card_proof: Proof, asset_address: ResourceAddress, amount: Option<Decimal>) -> Bucket
+        {
+            assert!(!self.is_paused(MethodGroup::Claims), "Claims are paused");
+            let validated_proof = self.check_proof(voter_card_proof);
+            let voter_card = self.get_voter_card_data_from_proof(&validated_proof);
+
+            let claimable = self.claimable_amount(voter_card.voter_id, asset_address);
+            assert!(claimable.is_positive(), "There is nothing to claim for this asset");
+
+            self.take_claimed_asset(voter_card.voter_id, asset_address, amount)
+        }
+
+
+        /// Internal function that makes a change of an accepted Proposal to this blueprint
+        ///
+        /// # Arguments
+        /// * `change` - change to make
+        fn execute_change(&mut self, change: Change)
+        {
+            match change
+            {
+                Change::AllowSpending(address, amount, to) =>
+                    {
+                        self.allow_spending(address, amount, to);
+                    }
 
-                        let mut resource_to_remove = vec![];
+                Change::AllowMinting(amount) =>
+                    {
+                        self.record_outflow(self.styx_address, amount);
+                        self.mint_styx(amount);
+                    }
 
-                        for (resource, amount) in hashmap.iter_mut()
-                        {
-                            let mut opt_bucket = None;
-                            let mut opt_resource_to_remove = None;
+                Change::SuspendVoter(voter_id, until_epoch) =>
+                    {
+                        self.suspended_voters.insert(voter_id, until_epoch);
+                        self.conviction_voting.withdraw_all_stakes(voter_id, Runtime::current_epoch());
+                    }
 
-                            let vault_to_take_from : Option<&mut Vault>;
+                Change::SlashLockedTokens(voter_id, amount) =>
+                    {
+                        self.slash_locked_tokens(voter_id, amount);
+                    }
 
-                            if *resource == self.styx_address
-                            {
-                                vault_to_take_from = Some(&mut self.styx_vault);
-                            }
-                            else {
-                                vault_to_take_from = self.assets_under_management.get_mut(resource);
-                            }
+                Change::ChangeMembershipBadge(membership_badge) =>
+                    {
+                        self.membership_badge = membership_badge;
+                    }
 
-                            match vault_to_take_from
-                            {
-                                None => {}
-                                Some(vault) =>
-                                    {
-                                        let mut new_bucket = Bucket::new(*resource);
-                                        let owned = vault.amount();
-                                        let amount_to_take = owned.max(*amount);
-
-                                        new_bucket.put(vault.take(amount_to_take));
-
-                                        if amount_to_take == owned
-                                        {
-                                            // If the resource is the DAO token then this line does not do anything
-                                            self.assets_under_management.remove(&resource);
-                                        }
-
-                                        *amount = *amount - amount_to_take;
-                                        opt_bucket = Some(new_bucket);
-                                        if amount.is_zero()
-                                        {
-                                            opt_resource_to_remove = Some(*resource);
-                                        }
-                                    }
-                            }
+                Change::ChangeEmissionSchedule(schedule) =>
+                    {
+                        self.emission = schedule.map(|schedule| Emission::new(schedule, Runtime::current_epoch()));
+                    }
 
-                            match opt_bucket
-                            {
-                                None => {},
-                                Some(bucket) => { buckets.push(bucket); }
-                            }
+                Change::BurnTreasuryTokens(amount) =>
+                    {
+                        // Burns at most the Styx tokens owned by the DAO
+                        let tokens = self.styx_vault.take(amount.min(self.styx_vault.amount()));
+                        self.burn(tokens);
+                    }
+
+                Change::RevokeExternalAdmin =>
+                    {
+                        self.external_admin_revoked = true;
+                    }
+
+                Change::UpdateTokenMetadata(key, value) =>
+                    {
+                        self.update_metadata(self.styx_address, key, value);
+                    }
+
+                Change::UpdateVoterCardMetadata(key, value) =>
+                    {
+                        self.update_metadata(self.voter_card_address, key, value);
+                    }
+
+                Change::CreateResource(new_resource) =>
+                    {
+                        self.create_resource(new_resource);
+                    }
+
+                Change::MintResource(address, amount) =>
+                    {
+                        self.record_outflow(address, amount);
+                        self.mint_resource(address, amount);
+                    }
+
+                Change::BurnResource(address, amount) =>
+                    {
+                        self.burn_resource(address, amount);
+                    }
+
+                Change::CallMethod(component, method, arguments, assets, return_kind) =>
+                    {
+                        self.call_method(component, method, arguments, assets, return_kind);
+                    }
+
+                Change::TransferNonFungibles(address, ids, to) =>
+                    {
+                        self.allow_non_fungible_spending(address, ids, to);
+                    }
+
+                Change::PayAccount(address, amount, account) =>
+                    {
+                        self.pay_account(address, amount, account);
+                    }
 
-                            match opt_resource_to_remove
+                Change::ChangeClaimPeriod(period) =>
+                    {
+                        self.claim_period = period;
+                    }
+
+                Change::ChangeOutflowLimit(address, limit) =>
+                    {
+                        change_outflow_limit(&mut self.outflow_limits, address, limit);
+                    }
+
+                Change::ChangeGuardianBadge(guardian_badge) =>
+                    {
+                        self.guardian_badge = guardian_badge;
+                    }
+
+                Change::ChangeMaxGuardianPause(max_pause) =>
+                    {
+                        self.pauses.max_guardian_pause = max_pause;
+                    }
+
+                Change::SetPause(group, until_epoch) =>
+                    {
+                        self.pauses.set_pause(group, until_epoch);
+                    }
+
+                Change::ChangeConvictionRules(decay, max_share, weight) =>
+                    {
+                        self.conviction_voting.change_rules(decay, max_share, weight);
+                    }
+                _ => { panic!("critical error in code. This should not happen.") }
+            }
+        }
+
+        /// Internal function that checks the changes of a new Proposal or amendment, so that values
+        /// that would make the execution panic are rejected when the Proposal is made
+        ///
+        /// # Arguments
+        /// * `changes` - changes of the Proposal
+        fn check_changes(&self, changes: &Vec<Change>)
+        {
+            for change in changes
+            {
+                match change
+                {
+                    Change::SlashLockedTokens(voter_id, amount) =>
+                        {
+                            assert!(*voter_id < self.new_voter_card_id, "This VoterCard does not exist");
+                            assert!(!amount.is_negative(), "Cannot slash a negative amount");
+                        }
+                    Change::ChangeEmissionSchedule(Some(schedule)) =>
+                        {
+                            schedule.check();
+                        }
+                    Change::BurnTreasuryTokens(amount) | Change::BurnResource(_, amount) =>
+                        {
+                            assert!(!amount.is_negative(), "Cannot burn a negative amount");
+                        }
+                    Change::CreateResource(new_resource) =>
+                        {
+                            new_resource.check();
+                        }
+                    Change::MintResource(address, amount) =>
+                        {
+                            // Resources created by proposals that are not executed yet are checked
+                            // when this Proposal is executed
+                            let can_mint = match self.created_resources.get(address)
                             {
-                                None => {},
-                                Some(resource_rem) => {resource_to_remove.push(resource_rem);}
-                            }
+                                None => !amount.is_negative(),
+                                Some(kind) => kind.can_mint(*amount)
+                            };
+                            assert!(can_mint, "This amount cannot be minted");
+                        }
+                    Change::ChangeConvictionRules(decay, max_share, weight) =>
+                        {
+                            ConvictionVoting::check_rules(*decay, *max_share, *weight);
+                        }
+                    Change::PayAccount(_, amount, account) =>
+                        {
+                            assert!(!amount.is_negative(), "Cannot pay a negative amount");
+                            let component = borrow_component!(*account);
+                            let is_account = component.package_address() == ACCOUNT_PACKAGE && component.blueprint_name() == "Account";
+                            assert!(is_account, "Payments can only be made to accounts");
+                        }
+                    Change::ChangeOutflowLimit(_, Some((max_amount, window))) =>
+                        {
+                            OutflowLimit::check(*max_amount, *window);
+                        }
+                    Change::TransferNonFungibles(_, ids, _) =>
+                        {
+                            let unique_ids: HashSet<&NonFungibleId> = ids.iter().collect();
+                            assert_eq!(unique_ids.len(), ids.len(), "The same item cannot be transferred twice");
+                        }
+                    Change::CallMethod(component, _, arguments, _, _) =>
+                        {
+                            assert!(*component != Runtime::actor().as_component().0, "A proposal cannot call the methods of the DAO");
+                            // Panics if the component does not exist
+                            borrow_component!(*component).package_address();
+                            let is_argument_list = matches!(sbor::decode_any(arguments), Ok(sbor::Value::Struct { .. }));
+                            assert!(is_argument_list, "The arguments should be an SBOR-encoded list of arguments");
                         }
+                    _ => {}
+                }
+            }
+        }
+
+        /// Internal function that checks that the changes of an accepted Proposal can be executed
+        /// as a whole with the assets currently owned by the DAO, under the outflow limits and
+        /// under the max supply, and that they only mint and burn resources created by the DAO.
+        /// Returns the reason why they cannot be executed otherwise.
+        ///
+        /// # Arguments
+        /// * `changes` - changes of the Proposal
+        fn check_execution(&self, changes: &Vec<Change>, fast_track: bool) -> Result<(), String>
+        {
+            let current_epoch = Runtime::current_epoch();
+
+            // Balances and outflow limits of the resources after each change, in the order of execution
+            let mut balances: HashMap<ResourceAddress, Decimal> = HashMap::new();
+            let mut outflow_limits = self.outflow_limits.clone();
+            let mut emitted_tokens = self.emitted_tokens;
+            // Non fungible ids already transferred by earlier changes of the Proposal
+            let mut transferred_ids: HashMap<ResourceAddress, HashSet<NonFungibleId>> = HashMap::new();
 
-                        for resource in resource_to_remove.into_iter()
+            // The limits may have changed since a fast-track Proposal was made
+            if fast_track
+            {
+                self.check_fast_track_limits(&outflow_limits, changes)?;
+            }
+
+            for change in changes
+            {
+                if let Change::ChangeOutflowLimit(address, limit) = change
+                {
+                    change_outflow_limit(&mut outflow_limits, *address, *limit);
+                }
+
+                if let Change::AllowMinting(amount) = change
+                {
+                    emitted_tokens = emitted_tokens + *amount;
+                    if emitted_tokens > self.max_supply
+                    {
+                        return Err(String::from("the minted tokens go over the max supply"));
+                    }
+                }
+
+                if let Change::MintResource(address, amount) = change
+                {
+                    let can_mint = self.created_resources.get(address).map_or(false, |kind| kind.can_mint(*amount));
+                    if !can_mint
+                    {
+                        return Err(format!("{} cannot be minted by the DAO", address));
+                    }
+                }
+
+                if let Change::BurnResource(address, amount) = change
+                {
+                    if !self.created_resources.contains_key(address)
+                    {
+                        return Err(format!("{} was not created by the DAO", address));
+                    }
+
+                    // Burns are not outflows, so only the balance is updated
+                    let balance = balances.entry(*address).or_insert(self.amount_owned(*address));
+                    *balance = *balance - *amount;
+                    if balance.is_negative()
+                    {
+                        return Err(format!("the DAO does not own enough of {}", address));
+                    }
+                }
+
+                if let Change::TransferNonFungibles(address, ids, _) = change
+                {
+                    let owned_ids: HashSet<NonFungibleId> = self.non_fungible_ids_owned(*address).into_iter().collect();
+                    let transferred = transferred_ids.entry(*address).or_insert(HashSet::new());
+                    for id in ids
+                    {
+                        if !owned_ids.contains(id) || !transferred.insert(id.clone())
                         {
-                            hashmap.remove(&resource);
+                            return Err(format!("the DAO does not own the item {} of {}", id, address));
                         }
+                    }
+                }
 
+                let flows: Vec<(ResourceAddress, Decimal)> = match change
+                {
+                    Change::AllowSpending(address, amount, _) | Change::PayAccount(address, amount, _) => vec![(*address, -*amount)],
+                    Change::CallMethod(_, _, _, assets, _) => assets.iter().map(|(address, amount)| (*address, -*amount)).collect(),
+                    Change::TransferNonFungibles(address, ids, _) => vec![(*address, -Decimal::from(ids.len()))],
+                    Change::AllowMinting(amount) => vec![(self.styx_address, *amount)],
+                    Change::MintResource(address, amount) => vec![(*address, *amount)],
+                    _ => vec![]
+                };
+
+                for (address, flow) in flows
+                {
+                    let balance = balances.entry(address).or_insert(self.amount_owned(address));
+                    *balance = *balance + flow;
+                    if balance.is_negative()
+                    {
+                        return Err(format!("the DAO does not own enough of {}", address));
+                    }
+
+                    // Both spending and minting count as outflows
+                    let outflow = if flow.is_negative() { -flow } else { flow };
+                    if let Some(limit) = outflow_limits.get_mut(&address)
+                    {
+                        if !limit.record(outflow, current_epoch)
+                        {
+                            return Err(format!("the outflows of {} go over their limit", address));
+                        }
                     }
+                }
             }
 
-            buckets
+            Ok(())
         }
 
+        /// Internal function that checks that the outflow limits set by a fast-track Proposal only
+        /// tighten the given limits. Returns the reason why they do not otherwise.
+        ///
+        /// # Arguments
+        /// * `outflow_limits` - outflow limits to compare with
+        /// * `changes` - changes of the fast-track Proposal
+        fn check_fast_track_limits(&self, outflow_limits: &HashMap<ResourceAddress, OutflowLimit>, changes: &Vec<Change>) -> Result<(), String>
+        {
+            for change in changes
+            {
+                if let Change::ChangeOutflowLimit(address, Some((max_amount, window))) = change
+                {
+                    if !tightens_outflow_limit(outflow_limits, *address, *max_amount, *window)
+                    {
+                        return Err(format!("a fast-track proposal loosens the outflow limit of {}", address));
+                    }
+                }
+            }
+            Ok(())
+        }
 
-        /// Internal function that adds a certain amount of asset owned to be claimable by a user
+        /// Internal function that reserves a certain amount of asset owned to be claimable by a user
+        /// until the end of the claim period
         ///
         /// # Arguments
         /// * `address` - address of the asset
@@ -490,28 +1428,333 @@ blueprint! {
         /// * `to` - user that can claim the asset
         fn allow_spending(&mut self, address: ResourceAddress, amount: Decimal, to: u64)
         {
+            let reserved = self.take_from_treasury(address, amount);
+            match self.claims_escrow.get_mut(&address)
+            {
+                None => { self.claims_escrow.insert(address, Vault::with_bucket(reserved)); }
+                Some(escrow) => { escrow.put(reserved); }
+            }
+
+            let expiration_epoch = Runtime::current_epoch() + self.claim_period;
+            self.claimable_assets
+                .entry(to)
+                .or_insert(HashMap::new())
+                .entry(address)
+                .or_insert(ClaimableAsset::new())
+                .add_grant(amount, expiration_epoch);
+        }
+
+        /// Internal function that mints a certain amount of Styx tokens and deposit them in the
+        /// styx_vault. The total of emitted tokens can never go over the max supply.
+        ///
+        /// # Arguments
+        /// * `amount` - amount of Styx tokens to mint
+        fn mint_styx(&mut self, amount: Decimal)
+        {
+            assert!(!self.is_paused(MethodGroup::Minting), "Minting is paused");
+            assert!(self.emitted_tokens + amount <= self.max_supply, "Cannot emit more tokens than the max supply");
+            let bucket = self.internal_authority.authorize(|| {
+                borrow_resource_manager!(self.styx_address).mint(amount)
+            });
+            self.emitted_tokens = self.emitted_tokens + amount;
+            self.styx_vault.put(bucket);
+        }
+
+        /// Internal function that sets a metadata field of a resource managed by the DAO
+        ///
+        /// # Arguments
+        /// * `resource_address` - address of the resource
+        /// * `key` - name of the metadata field
+        /// * `value` - new value of the metadata field
+        fn update_metadata(&self, resource_address: ResourceAddress, key: String, value: String)
+        {
+            let resource_manager: &mut ResourceManager = borrow_resource_manager!(resource_address);
+            self.internal_authority
+                .authorize(|| resource_manager.set_metadata(key, value));
+        }
+
+        /// Internal function that creates a new resource whose mint and burn authority belong to the
+        /// DAO and puts its initial supply in the assets_under_management
+        ///
+        /// # Arguments
+        /// * `new_resource` - description of the resource to create
+        fn create_resource(&mut self, new_resource: NewResource)
+        {
+            let internal_access: AccessRule = rule!(require(self.internal_authority.resource_address()));
 
-            match self.claimable_assets.get_mut(&to)
+            let resource_address = match new_resource.kind
             {
-                None =>
+                ResourceKind::Fungible(divisibility) =>
                     {
-                        let mut new_hashmap: HashMap<ResourceAddress, Decimal> = HashMap::new();
-                        new_hashmap.insert(address, amount);
-                        self.claimable_assets.insert(to, new_hashmap);
-                    },
-                Some(hashmap) =>
+                        ResourceBuilder::new_fungible()
+                            .divisibility(divisibility)
+                            .metadata("name", new_resource.name)
+                            .metadata("symbol", new_resource.symbol)
+                            .updateable_metadata(internal_access.clone(), LOCKED)
+                            .mintable(internal_access.clone(), LOCKED)
+                            .burnable(internal_access.clone(), LOCKED)
+                            .no_initial_supply()
+                    }
+                ResourceKind::NonFungible =>
                     {
-                        match hashmap.get_mut(&address)
+                        ResourceBuilder::new_non_fungible()
+                            .metadata("name", new_resource.name)
+                            .metadata("symbol", new_resource.symbol)
+                            .updateable_metadata(internal_access.clone(), LOCKED)
+                            .mintable(internal_access.clone(), LOCKED)
+                            .burnable(internal_access.clone(), LOCKED)
+                            .no_initial_supply()
+                    }
+            };
+
+            info!("New resource created: {}", resource_address);
+            self.created_resources.insert(resource_address, new_resource.kind);
+            self.assets_under_management.insert(resource_address, Vault::new(resource_address));
+            self.mint_resource(resource_address, new_resource.initial_supply);
+        }
+
+        /// Internal function that mints a certain amount of a resource created by the DAO and puts
+        /// it in the assets_under_management. For non fungible resources, the amount is the number
+        /// of DaoItems to mint.
+        ///
+        /// # Arguments
+        /// * `resource_address` - address of the resource to mint
+        /// * `amount` - amount to mint
+        fn mint_resource(&mut self, resource_address: ResourceAddress, amount: Decimal)
+        {
+            assert!(!self.is_paused(MethodGroup::Minting), "Minting is paused");
+
+            let resource_manager: &mut ResourceManager = borrow_resource_manager!(resource_address);
+
+            let bucket = match self.created_resources.get(&resource_address)
+            {
+                None => { panic!("This resource was not created by the DAO"); }
+                Some(ResourceKind::Fungible(_)) =>
+                    {
+                        self.internal_authority.authorize(|| resource_manager.mint(amount))
+                    }
+                Some(ResourceKind::NonFungible) =>
+                    {
+                        // The amount was checked to be a bounded integer number of items
+                        let mut bucket = Bucket::new(resource_address);
+                        let mut minted = Decimal::zero();
+                        while minted < amount
                         {
-                            None => { hashmap.insert(address, amount); }
-                            Some(tokens) =>
-                                {
-                                    *tokens = *tokens + amount;
-                                }
+                            let item = DaoItem { minted_epoch: Runtime::current_epoch() };
+                            let id = NonFungibleId::from_u64(self.new_item_id);
+                            bucket.put(self.internal_authority.authorize(|| resource_manager.mint_non_fungible(&id, item)));
+                            self.new_item_id += 1;
+                            minted = minted + 1;
                         }
+                        bucket
                     }
+            };
+
+            self.gift_asset(bucket);
+        }
+
+        /// Internal function that burns a certain amount of a resource created by the DAO and owned
+        /// by the DAO
+        ///
+        /// # Arguments
+        /// * `resource_address` - address of the resource to burn
+        /// * `amount` - amount to burn
+        fn burn_resource(&mut self, resource_address: ResourceAddress, amount: Decimal)
+        {
+            assert!(self.created_resources.contains_key(&resource_address), "This resource was not created by the DAO");
+
+            let vault = self.assets_under_management.get_mut(&resource_address).expect("The DAO does not own this resource");
+            let bucket = vault.take(amount);
+            self.internal_authority.authorize(|| bucket.burn());
+        }
+
+        /// Internal function that calls a method of a component with the authority of the DAO.
+        /// The buckets are passed as first argument of the method, followed by the given
+        /// arguments. The buckets returned by the method are put in the assets owned by the DAO.
+        ///
+        /// # Arguments
+        /// * `component` - address of the component to call
+        /// * `method` - name of the method to call
+        /// * `arguments` - SBOR-encoded list of the other arguments of the method, as built by `args!`
+        /// * `assets` - assets owned by the DAO to pass to the method
+        /// * `return_kind` - what the method returns
+        fn call_method(&mut self, component: ComponentAddress, method: String, arguments: Vec<u8>, assets: Vec<(ResourceAddress, Decimal)>, return_kind: ReturnKind)
+        {
+            let mut buckets: Vec<Bucket> = vec![];
+            for (address, amount) in assets
+            {
+                buckets.push(self.take_from_treasury(address, amount));
             }
 
+            // Splice the buckets in front of the encoded arguments, the same way args! builds them.
+            // The arguments were checked when the Proposal was made
+            let mut fields = match sbor::decode_any(&arguments)
+            {
+                Ok(sbor::Value::Struct { fields }) => fields,
+                _ => panic!("The arguments should be an SBOR-encoded list of arguments")
+            };
+            let encoded_buckets = scrypto_encode(&buckets);
+            fields.insert(0, sbor::decode_any(&encoded_buckets).unwrap());
+            let call_arguments = sbor::encode_any(&sbor::Value::Struct { fields });
+
+            let returned_buckets: Vec<Bucket> = self.internal_authority.authorize(|| {
+                let called = borrow_component!(component);
+                match return_kind
+                {
+                    ReturnKind::Nothing =>
+                        {
+                            called.call::<()>(&method, call_arguments);
+                            vec![]
+                        }
+                    ReturnKind::Bucket => vec![called.call::<Bucket>(&method, call_arguments)],
+                    ReturnKind::Buckets => called.call::<Vec<Bucket>>(&method, call_arguments)
+                }
+            });
+
+            for bucket in returned_buckets
+            {
+                self.gift_asset(bucket);
+            }
+        }
+
+        /// Internal function that takes a certain amount of an asset owned by the DAO
+        ///
+        /// # Arguments
+        /// * `address` - address of the asset
+        /// * `amount` - amount to take
+        fn take_from_treasury(&mut self, address: ResourceAddress, amount: Decimal) -> Bucket
+        {
+            self.record_outflow(address, amount);
+
+            let vault = if address == self.styx_address
+            {
+                &mut self.styx_vault
+            }
+            else
+            {
+                self.assets_under_management.get_mut(&address).expect("The DAO does not own this asset")
+            };
+
+            assert!(vault.amount() >= amount, "The DAO does not own enough of this asset");
+            vault.take(amount)
+        }
+
+        /// Internal function that takes an asset claimed by a user from the assets reserved for the
+        /// claims and updates the grants of the user
+        ///
+        /// # Arguments
+        /// * `voter_id` - id of the user claiming the asset
+        /// * `address` - address of the asset to claim
+        /// * `amount` - optional amount to claim. If None, everything that can be claimed is taken
+        fn take_claimed_asset(&mut self, voter_id: u64, address: ResourceAddress, amount: Option<Decimal>) -> Bucket
+        {
+            let current_epoch = Runtime::current_epoch();
+            let amount_to_take = match amount
+            {
+                None => self.claimable_amount(voter_id, address),
+                Some(amount) =>
+                    {
+                        assert!(!amount.is_negative(), "Cannot claim a negative amount");
+                        amount
+                    }
+            };
+
+            let hashmap = self.claimable_assets.get_mut(&voter_id).unwrap();
+            let claimable_asset = hashmap.get_mut(&address).unwrap();
+            claimable_asset.claim(amount_to_take, current_epoch);
+            if claimable_asset.is_empty()
+            {
+                hashmap.remove(&address);
+                if hashmap.is_empty()
+                {
+                    self.claimable_assets.remove(&voter_id);
+                }
+            }
+
+            self.claims_escrow.get_mut(&address).unwrap().take(amount_to_take)
+        }
+
+        /// Internal function that records an outflow of a resource decided by a proposal. The
+        /// outflows of a proposal are checked before it is executed, so this only panics if the
+        /// check was bypassed.
+        ///
+        /// # Arguments
+        /// * `address` - address of the resource
+        /// * `amount` - amount flowing out
+        fn record_outflow(&mut self, address: ResourceAddress, amount: Decimal)
+        {
+            match self.outflow_limits.get_mut(&address)
+            {
+                None => {}
+                Some(limit) =>
+                    {
+                        assert!(limit.record(amount, Runtime::current_epoch()), "This outflow goes over the limit of the current window");
+                    }
+            }
+        }
+
+        /// Internal function that deposits assets owned by the DAO into an account. The account
+        /// was checked to be an account when the Proposal was made.
+        ///
+        /// # Arguments
+        /// * `address` - address of the resource to pay
+        /// * `amount` - amount to pay
+        /// * `account` - address of the account receiving the payment
+        fn pay_account(&mut self, address: ResourceAddress, amount: Decimal, account: ComponentAddress)
+        {
+            let payment = self.take_from_treasury(address, amount);
+            borrow_component!(account).call::<()>("deposit", args!(payment));
+        }
+
+        /// Internal function that slashes locked tokens of a user and puts them in the styx_vault.
+        /// If the user has less tokens locked than the given amount, all its tokens are slashed.
+        ///
+        /// # Arguments
+        /// * `voter_id` - id of the VoterCard to slash
+        /// * `amount` - amount of tokens to slash
+        fn slash_locked_tokens(&mut self, voter_id: u64, amount: Decimal)
+        {
+            let id = NonFungibleId::from_u64(voter_id);
+            let mut voter_card = self.get_voter_card_data_from_id(&id);
+            let slashed_amount = voter_card.slash_tokens(amount);
+            self.change_data_from_id(&id, voter_card);
+
+            let slashed_tokens = self.locker_vault.take(slashed_amount);
+            self.styx_vault.put(slashed_tokens);
+
+            // The stakes could exceed the voting power left to the user
+            self.conviction_voting.withdraw_all_stakes(voter_id, Runtime::current_epoch());
+        }
+
+        /// Internal function that moves non fungible assets owned by the DAO to the escrow so that
+        /// they can only be claimed by a given user
+        ///
+        /// # Arguments
+        /// * `address` - address of the non fungible resource
+        /// * `ids` - ids of the non fungible assets to be claimable
+        /// * `to` - user that can claim the assets
+        fn allow_non_fungible_spending(&mut self, address: ResourceAddress, ids: Vec<NonFungibleId>, to: u64)
+        {
+            self.record_outflow(address, Decimal::from(ids.len()));
+            let vault = self.assets_under_management.get_mut(&address).expect("The DAO does not own this asset");
+            let mut bucket = Bucket::new(address);
+            for id in ids.iter()
+            {
+                bucket.put(vault.take_non_fungible(id));
+            }
+
+            match self.non_fungible_escrow.get_mut(&address)
+            {
+                None => { self.non_fungible_escrow.insert(address, Vault::with_bucket(bucket)); }
+                Some(escrow) => { escrow.put(bucket); }
+            }
+
+            let claimable = self.claimable_non_fungibles
+                .entry(to)
+                .or_insert(HashMap::new())
+                .entry(address)
+                .or_insert(vec![]);
+            claimable.extend(ids);
         }
 
         /// Internal function that changes the data of a VoterCard
@@ -521,15 +1764,25 @@ blueprint! {
         /// * `new_voter_card` - new data of the VoterCard
         fn change_data(&self, valid_proof: &ValidatedProof, new_voter_card: VoterCard)
         {
-            let resource_manager : &mut ResourceManager = borrow_resource_manager!(self.voter_card_address);
             let id = valid_proof.non_fungible::<VoterCard>().id();
+            self.change_data_from_id(&id, new_voter_card);
+        }
+
+        /// Internal function that changes the data of the VoterCard with the given id
+        ///
+        /// # Arguments
+        /// * `id` - NonFungibleId of the VoterCard
+        /// * `new_voter_card` - new data of the VoterCard
+        fn change_data_from_id(&self, id: &NonFungibleId, new_voter_card: VoterCard)
+        {
+            let resource_manager : &mut ResourceManager = borrow_resource_manager!(self.voter_card_address);
             self.internal_authority
-                .authorize(|| resource_manager.update_non_fungible_data(&id, new_voter_card));
+                .authorize(|| resource_manager.update_non_fungible_data(id, new_voter_card));
         }
 
 
         /// Internal function that checks that a given Proof corresponds to a unique VoterCard Proof
-        /// and returns a ValidatedProof if so
+        /// that is not suspended and returns a ValidatedProof if so
         ///
         /// # Arguments
         /// * `voter_card_proof` - proof of a user's VoterCard
@@ -545,19 +1798,138 @@ blueprint! {
                         )
             ).expect("Invalid proof provided");
 
+            let voter_id = self.get_voter_card_data_from_proof(&valid_proof).voter_id;
+            assert!(!self.is_suspended(voter_id), "This VoterCard is suspended");
+
             valid_proof
         }
 
+        /// Internal function that checks that a given Proof corresponds to a unique VotingKey that
+        /// has not been revoked and whose VoterCard is not suspended. Returns the id of the VoterCard
+        /// that authorized the VotingKey if so
+        ///
+        /// # Arguments
+        /// * `voting_key_proof` - proof of a user's VotingKey
+        fn check_voting_key_proof(&self, voting_key_proof: Proof) -> NonFungibleId
+        {
+            let valid_proof: ValidatedProof = voting_key_proof.validate_proof
+            (
+                ProofValidationMode::ValidateContainsAmount
+                    (
+                        self.voting_key_address,
+                        dec!(1)
+                    )
+            ).expect("Invalid proof provided");
+
+            let voting_key: VotingKey = valid_proof.non_fungible::<VotingKey>().data();
+            assert_eq!(self.active_voting_keys.get(&voting_key.key_id), Some(&voting_key.voter_id), "This VotingKey has been revoked");
+            assert!(!self.is_suspended(voting_key.voter_id), "This VoterCard is suspended");
+
+            NonFungibleId::from_u64(voting_key.voter_id)
+        }
+
+        /// Internal function that checks that a proof of the membership badge is provided when
+        /// membership is gated
+        ///
+        /// # Arguments
+        /// * `membership_proof` - proof of the membership badge
+        fn check_membership(&self, membership_proof: Option<Proof>)
+        {
+            match self.membership_badge
+            {
+                None => {}
+                Some(badge_address) =>
+                    {
+                        membership_proof
+                            .expect("A proof of the membership badge is required to mint a VoterCard")
+                            .validate_proof(ProofValidationMode::ValidateResourceAddress(badge_address))
+                            .expect("Invalid membership proof provided");
+                    }
+            }
+        }
+
+        /// Internal function that returns the supply of DAO tokens used as a reference for new
+        /// proposals
+        fn reference_supply(&self) -> Decimal
+        {
+            let reference_supply = match self.ballot_box.supply_reference()
+            {
+                SupplyReference::EmittedTokens => self.emitted_tokens,
+                SupplyReference::CirculatingSupply => self.circulating_supply()
+            };
+            assert!(reference_supply.is_positive(), "Cannot make a proposal while the reference supply is zero");
+            reference_supply
+        }
+
+        /// Internal function that executes a funding request if its conviction passed the threshold.
+        /// Nothing is executed while the execution of proposals is paused or if the request goes over
+        /// the outflow limit of the resource.
+        ///
+        /// # Arguments
+        /// * `request_id` - id of the funding request
+        fn try_execute_funding_request(&mut self, request_id: usize)
+        {
+            let (resource, amount, beneficiary) = match self.conviction_voting.requests.get(request_id)
+            {
+                None => { panic!("This funding request does not exist!") }
+                Some(request) => (request.resource, request.amount, request.beneficiary)
+            };
+
+            let within_outflow_limit = self.outflow_available(resource).map_or(true, |available| amount <= available);
+            if self.is_paused(MethodGroup::ProposalExecution) || !within_outflow_limit
+            {
+                return;
+            }
+
+            let treasury_amount = self.amount_owned(resource);
+            let reference_supply = self.reference_supply();
+            if self.conviction_voting.try_execute(request_id, treasury_amount, reference_supply, Runtime::current_epoch())
+            {
+                self.allow_spending(resource, amount, beneficiary);
+            }
+        }
+
+        /// Internal function that returns whether a given method group is currently paused
+        ///
+        /// # Arguments
+        /// * `group` - method group to check
+        fn is_paused(&self, group: MethodGroup) -> bool
+        {
+            self.pauses.is_paused(&group, Runtime::current_epoch())
+        }
+
+        /// Internal function that returns whether a given VoterCard is currently suspended
+        ///
+        /// # Arguments
+        /// * `voter_id` - id of the VoterCard to check
+        fn is_suspended(&self, voter_id: u64) -> bool
+        {
+            match self.suspended_voters.get(&voter_id)
+            {
+                None => false,
+                Some(until_epoch) => Runtime::current_epoch() < *until_epoch
+            }
+        }
+
         /// Internal function that extracts the VoterCard data associated to a ValidatedProof
         ///
         /// # Arguments
         /// * `validated_proof` - ValidatedProof associated to a user's VoterCard
         fn get_voter_card_data_from_proof(&self, validated_proof: &ValidatedProof) -> VoterCard
+        {
+            let id = validated_proof.non_fungible::<VoterCard>().id();
+            self.get_voter_card_data_from_id(&id)
+        }
+
+        /// Internal function that extracts the data of the VoterCard with the given id
+        ///
+        /// # Arguments
+        /// * `id` - NonFungibleId of the VoterCard
+        fn get_voter_card_data_from_id(&self, id: &NonFungibleId) -> VoterCard
         {
             let resource_manager: &ResourceManager =
                 borrow_resource_manager!(self.voter_card_address);
-            let id = validated_proof.non_fungible::<VoterCard>().id();
-            resource_manager.get_non_fungible_data::<VoterCard>(&id)
+            resource_manager.get_non_fungible_data::<VoterCard>(id)
         }
     }
 }
\ No newline at end of file
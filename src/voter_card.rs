@@ -29,6 +29,19 @@ pub struct VoterCard {
     pub approved_voters: Vec<u64>
 }
 
+/// A voting key, minted by the DAO on behalf of a VoterCard. It can be held by another account than
+/// the VoterCard and used to support and vote for proposals with the voting power of the VoterCard,
+/// but it cannot be used to unlock tokens, claim assets or delegate.
+#[derive(NonFungibleData)]
+pub struct VotingKey {
+
+    /// Id of the voting key
+    pub key_id: u64,
+
+    /// Id of the VoterCard that authorized the voting key
+    pub voter_id: u64
+}
+
 impl VoterCard
 {
     /// Instantiates a new voter card from an id and an amount of tokens
@@ -222,18 +235,39 @@ impl VoterCard
         }
         else
         {
-            self.total_number_of_token -= amount;
-            let mut amount_loop = amount;
-            while amount_loop > dec!(0)
-            {
-                let (tokens,time) = self.locked_tokens.pop().unwrap();
-                if tokens > amount
-                {
-                    self.locked_tokens.push( (tokens- amount_loop,time));
-                }
+            self.remove_last_tokens(amount);
+        }
+    }
 
-                amount_loop = amount_loop - tokens;
-            }
+    /// Slashes a certain amount of locked tokens from the voter card and returns the amount that
+    /// was actually slashed. The locked balance never gets negative: if the voter card holds less
+    /// than the given amount, all of its tokens are slashed.
+    /// Like retrievals, the last locked tokens are slashed first.
+    ///
+    /// # Arguments
+    /// * `amount` - amount of tokens to slash
+    ///
+    /// # Examples
+    /// ```
+    /// use scrypto::dec;
+    /// use styx::voter_card::VoterCard;
+    ///
+    /// let mut new_voter_card = VoterCard::new(0);
+    /// new_voter_card.add_tokens(dec!(10), 0);
+    /// let slashed = new_voter_card.slash_tokens(dec!(15));
+    /// assert_eq!(slashed, dec!(10));
+    /// assert_eq!(new_voter_card.total_number_of_token, dec!(0));
+    /// ```
+    pub fn slash_tokens(&mut self, amount: Decimal) -> Decimal
+    {
+        if amount >= self.total_number_of_token
+        {
+            self.retrieve_all_tokens()
+        }
+        else
+        {
+            self.remove_last_tokens(amount);
+            amount
         }
     }
 
@@ -319,6 +353,28 @@ impl VoterCard
         time_multiplicator
     }
 
+    /// Internal function that removes a given amount of tokens from the last locked tokens.
+    /// The amount should be strictly less than the total number of tokens.
+    ///
+    /// # Arguments
+    /// * `amount` - amount of tokens to remove
+    ///
+    fn remove_last_tokens(&mut self, amount: Decimal)
+    {
+        self.total_number_of_token -= amount;
+        let mut amount_loop = amount;
+        while amount_loop > dec!(0)
+        {
+            let (tokens,time) = self.locked_tokens.pop().unwrap();
+            if tokens > amount_loop
+            {
+                self.locked_tokens.push( (tokens - amount_loop,time));
+            }
+
+            amount_loop = amount_loop - tokens;
+        }
+    }
+
     /// Internal function used to merge the list of locked tokens
     ///
     /// # Arguments
@@ -427,4 +483,41 @@ mod tests
             assert_eq!(voter_card.votes.get(i).unwrap().0, i);
         }
     }
-}
+
+    #[test]
+    fn test_retrieve_tokens_over_several_locks()
+    {
+        let mut voter_card = VoterCard::new(0);
+        voter_card.add_tokens(dec!(5), 0);
+        voter_card.add_tokens(dec!(5), 1);
+        voter_card.retrieve_tokens(dec!(7));
+
+        assert_eq!(voter_card.total_number_of_token, dec!(3));
+        assert_eq!(voter_card.locked_tokens, vec![(dec!(3), 0)]);
+    }
+
+    #[test]
+    fn test_slash_tokens()
+    {
+        let mut voter_card = VoterCard::new(0);
+        voter_card.add_tokens(dec!(10), 0);
+        voter_card.add_tokens(dec!(4), 1);
+        let slashed = voter_card.slash_tokens(dec!(6));
+
+        assert_eq!(slashed, dec!(6));
+        assert_eq!(voter_card.total_number_of_token, dec!(8));
+        assert_eq!(voter_card.locked_tokens, vec![(dec!(8), 0)]);
+    }
+
+    #[test]
+    fn test_slash_more_than_locked()
+    {
+        let mut voter_card = VoterCard::new(0);
+        voter_card.add_tokens(dec!(10), 0);
+        let slashed = voter_card.slash_tokens(dec!(25));
+
+        assert_eq!(slashed, dec!(10));
+        assert_eq!(voter_card.total_number_of_token, dec!(0));
+        assert!(voter_card.locked_tokens.is_empty());
+    }
+}
\ No newline at end of file
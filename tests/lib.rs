@@ -19,6 +19,8 @@ use lazy_static::lazy_static;
 use scrypto::prelude::*;
 
 const RADIX_TOKEN: &str = "resource_sim1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqzqu57yag";
+const DISCUSSION_URL: &str = "https://forum.example.com/proposal";
+const CONTENT_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
 
 
 #[derive(Debug)]
@@ -92,6 +94,7 @@ struct DAO_component {
     internal_admin_address : String,
     styx_address: String,
     voter_card_address: String,
+    voting_key_address: String,
 }
 
 impl DAO_component
@@ -183,6 +186,25 @@ impl DAO_component
         }
         return_dec
     }
+
+    pub fn get_circulating_supply(&self, account_address: &str) -> Decimal
+    {
+        let output = circulating_supply(account_address, &self.address);
+        parse_returned_decimal(&output).expect("Failed to parse circulating supply")
+    }
+}
+
+/// Returns the last Decimal returned in the output of a transaction, looking for a line of the
+/// form ├─ Decimal("90")
+fn parse_returned_decimal(output: &str) -> Option<Decimal>
+{
+    lazy_static! {
+        static ref RE_DECIMAL: Regex = Regex::new(r#"─ Decimal\("([\d.]*)"\)"#).unwrap();
+    }
+
+    RE_DECIMAL.captures_iter(output)
+        .last()
+        .map(|captures| Decimal::from(&captures[1]))
 }
 
 
@@ -289,40 +311,15 @@ fn instantiate(account_addr: &str, package_addr: &str)
                              .arg("rtm/instantiate.rtm")
                              .env("account", account_addr)
                              .env("package", &package_addr)
-                             .env("initial_supply", "100"));
+                             .env("initial_supply", "100")
+                             .env("max_supply", "10000"));
 
 
 
 
     println!("{}",output);
 
-    let result = output.split("\n").collect::<Vec<&str>>();
-
-    let i = 4 ; // for translation due to more info
-
-    let dao_address = result[13+i];
-    let external_admin_address = result[14+i];
-    let internal_admin_address = result[15+i];
-    let styx_address = result[16+i];
-    let voter_card_address = result[17+i];
-
-    let dao_address = dao_address.split(" ").collect::<Vec<&str>>()[2];
-    let external_admin_address = external_admin_address.split(" ").collect::<Vec<&str>>()[2];
-    let internal_admin_address = internal_admin_address.split(" ").collect::<Vec<&str>>()[2];
-    let styx_address = styx_address.split(" ").collect::<Vec<&str>>()[2];
-    let voter_card_address = voter_card_address.split(" ").collect::<Vec<&str>>()[2];
-
-
-
-
-    let dao = DAO_component {
-        address: String::from(dao_address),
-        external_admin_address: String::from(external_admin_address),
-        internal_admin_address : String::from(internal_admin_address),
-        styx_address: String::from(styx_address),
-        voter_card_address: String::from(voter_card_address),
-    };
-    dao
+    parse_dao_component(&output)
 }
 
 
@@ -332,47 +329,81 @@ fn instantiate(account_addr: &str, package_addr: &str)
 /// Returns the dao created.
 fn instantiate_custom(account_addr: &str, package_addr: &str, admin_badge_addr: &str)
                                    -> DAO_component
+{
+    instantiate_from_manifest(account_addr, package_addr, admin_badge_addr, "None", "None", "None")
+}
+
+/// Creates a new Dao via
+/// rtm/instantiate_custom.rtm
+/// The emission schedule, membership badge and external admin expiry are given as manifest values.
+///
+/// Returns the dao created.
+fn instantiate_from_manifest(account_addr: &str, package_addr: &str, admin_badge_addr: &str, emission_schedule: &str, membership_badge: &str, external_admin_expiry: &str)
+                             -> DAO_component
 {
     let output = run_command(Command::new("resim")
                              .arg("run")
-                             .arg("rtm/instantiate.rtm")
+                             .arg("rtm/instantiate_custom.rtm")
                              .env("account", account_addr)
                              .env("package", &package_addr)
                              .env("admin_badge", admin_badge_addr)
-                             .env("initial_supply", "100"));
+                             .env("initial_supply", "100")
+                             .env("max_supply", "10000")
+                             .env("emission_schedule", emission_schedule)
+                             .env("membership_badge", membership_badge)
+                             .env("external_admin_expiry", external_admin_expiry));
 
+    parse_dao_component(&output)
+}
 
+/// Parses the addresses of a Dao from the output of its instantiation
+fn parse_dao_component(output: &str) -> DAO_component
+{
+    lazy_static! {
+        static ref RE_COMPONENT: Regex = Regex::new(r"Component: (\w*)").unwrap();
+        static ref RE_EXTERNAL_ADMIN: Regex = Regex::new(r"external_admin : (\w*)").unwrap();
+        static ref RE_INTERNAL_ADMIN: Regex = Regex::new(r"internal_admin : (\w*)").unwrap();
+        static ref RE_STYX: Regex = Regex::new(r"styx : (\w*)").unwrap();
+        static ref RE_VOTER_CARD: Regex = Regex::new(r"voter_card : (\w*)").unwrap();
+        static ref RE_VOTING_KEY: Regex = Regex::new(r"voting_key : (\w*)").unwrap();
+    }
 
+    DAO_component {
+        address: RE_COMPONENT.captures(output).expect("Failed to parse dao address")[1].to_string(),
+        external_admin_address: RE_EXTERNAL_ADMIN.captures(output).expect("Failed to parse external admin address")[1].to_string(),
+        internal_admin_address: RE_INTERNAL_ADMIN.captures(output).expect("Failed to parse internal admin address")[1].to_string(),
+        styx_address: RE_STYX.captures(output).expect("Failed to parse styx address")[1].to_string(),
+        voter_card_address: RE_VOTER_CARD.captures(output).expect("Failed to parse voter card address")[1].to_string(),
+        voting_key_address: RE_VOTING_KEY.captures(output).expect("Failed to parse voting key address")[1].to_string(),
+    }
+}
 
-    //println!("{}",output);
-
-    let result = output.split("\n").collect::<Vec<&str>>();
-
-    let i = 4 ; // for translation due to more info
-
-    let dao_address = result[13+i];
-    let external_admin_address = result[14+i];
-    let internal_admin_address = result[15+i];
-    let styx_address = result[16+i];
-    let voter_card_address = result[17+i];
-
-    let dao_address = dao_address.split(" ").collect::<Vec<&str>>()[2];
-    let external_admin_address = external_admin_address.split(" ").collect::<Vec<&str>>()[2];
-    let internal_admin_address = internal_admin_address.split(" ").collect::<Vec<&str>>()[2];
-    let styx_address = styx_address.split(" ").collect::<Vec<&str>>()[2];
-    let voter_card_address = voter_card_address.split(" ").collect::<Vec<&str>>()[2];
-
+/// Creates a new MockProtocol via
+/// rtm/instantiate_mock_protocol.rtm
+///
+/// Returns the address of the component and of its reward token.
+fn instantiate_mock_protocol(account_addr: &str, package_addr: &str) -> (String, String)
+{
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/instantiate_mock_protocol.rtm")
+                             .env("account", account_addr)
+                             .env("package", &package_addr));
 
+    lazy_static! {
+        static ref RE_COMPONENT: Regex = Regex::new(r"Component: (\w*)").unwrap();
+        static ref RE_REWARD: Regex = Regex::new(r"reward : (\w*)").unwrap();
+    }
 
+    (RE_COMPONENT.captures(&output).expect("Failed to parse mock protocol address")[1].to_string(),
+     RE_REWARD.captures(&output).expect("Failed to parse reward address")[1].to_string())
+}
 
-    let dao = DAO_component {
-        address: String::from(dao_address),
-        external_admin_address: String::from(external_admin_address),
-        internal_admin_address : String::from(internal_admin_address),
-        styx_address: String::from(styx_address),
-        voter_card_address: String::from(voter_card_address),
-    };
-    dao
+/// Returns the manifest representation of SBOR-encoded bytes
+fn manifest_bytes(bytes: &[u8]) -> String
+{
+    let bytes = bytes.iter().map(|byte| format!("{}u8", byte)).collect::<Vec<String>>();
+    format!("Vec<U8>({})", bytes.join(", "))
 }
 
 
@@ -387,6 +418,18 @@ fn mint_voter_card_with_bucket(account_addr: &str,dao_address : &str , styx_addr
     output
 }
 
+fn mint_voter_card_with_membership(account_addr: &str, dao_address : &str , styx_address : &str, membership_badge_address : &str, bucket_amount : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/mint_voter_card_with_membership.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("styx", styx_address)
+                             .env("membership_badge", membership_badge_address)
+                             .env("amount", bucket_amount));
+    output
+}
+
 
 fn withdraw(account_addr: &str,dao_address : &str , external_badge_address : &str, amount : &str) -> String {
     let output = run_command(Command::new("resim")
@@ -414,6 +457,16 @@ fn emit(account_addr: &str,dao_address : &str , external_badge_address : &str, a
 }
 
 
+fn trigger_emission(account_addr: &str, dao_address : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/trigger_emission.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address));
+    output
+}
+
+
 fn lock(account_addr: &str, dao_address : &str , voter_card_address : &str, styx_address : &str, bucket_amount : &str) -> String {
     let output = run_command(Command::new("resim")
                              .arg("run")
@@ -482,6 +535,16 @@ fn advance_with_proposal(account_addr: &str, dao_address : &str , proposal_id :
     output
 }
 
+fn fail_stuck_proposal(account_addr: &str, dao_address : &str , proposal_id : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/fail_stuck_proposal.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("proposal_id", proposal_id));
+    output
+}
+
 fn delegate_for_proposal(account_addr: &str, dao_address : &str, voter_card_address : &str , proposal_id : &str, deleguate_to : &str) -> String {
     let output = run_command(Command::new("resim")
                              .arg("run")
@@ -497,12 +560,64 @@ fn delegate_for_proposal(account_addr: &str, dao_address : &str, voter_card_addr
 fn vote_for_proposal(account_addr: &str, dao_address : &str, voter_card_address : &str , proposal_id : &str, vote : &str) -> String {
     let output = run_command(Command::new("resim")
                              .arg("run")
-                             .arg("rtm/delegate_for_proposal.rtm")
+                             .arg("rtm/vote_for_proposal.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("proposal_id", proposal_id)
+                             .env("vote", format!("Enum(\"{}\")", vote)));
+    output
+}
+
+fn vote_for_options(account_addr: &str, dao_address : &str, voter_card_address : &str , proposal_id : &str, options : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/vote_for_proposal.rtm")
                              .env("account", account_addr)
                              .env("dao", &dao_address)
                              .env("voter_card", voter_card_address)
                              .env("proposal_id", proposal_id)
-                             .env("vote", vote));
+                             .env("vote", format!("Enum(\"Options\", Vec<U64>({}))", options)));
+    output
+}
+
+fn make_multiple_choice_proposal(account_addr: &str, dao_address : &str, voter_card_address : &str, title : &str, options : &str, mode : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/make_multiple_choice_proposal.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("title", title)
+                             .env("summary", title)
+                             .env("discussion_url", DISCUSSION_URL)
+                             .env("content_hash", CONTENT_HASH)
+                             .env("options", options)
+                             .env("mode", mode));
+    output
+}
+
+fn make_poll(account_addr: &str, dao_address : &str, voter_card_address : &str, description : &str, options : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/make_poll.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("description", description)
+                             .env("options", options));
+    output
+}
+
+fn vote_for_poll(account_addr: &str, dao_address : &str, voter_card_address : &str, poll_id : &str, option : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/vote_for_poll.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("poll_id", poll_id)
+                             .env("option", option));
     output
 }
 
@@ -539,6 +654,27 @@ fn amount_locked(account_addr: &str, dao_address: &str) -> String {
 
 }
 
+fn circulating_supply(account_addr: &str, dao_address: &str) -> String {
+    let output = run_command(Command::new("resim")
+                            .arg("run")
+                            .arg("rtm/circulating_supply.rtm")
+                            .env("account", account_addr)
+                            .env("dao", &dao_address));
+
+    output
+}
+
+fn burn(account_addr: &str, dao_address : &str, styx_address : &str, amount : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/burn.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("styx", styx_address)
+                             .env("amount", amount));
+    output
+}
+
 fn claim_asset(account_addr: &str, dao_address : &str , voter_card_address : &str) -> String {
     let output = run_command(Command::new("resim")
                              .arg("run")
@@ -549,87 +685,352 @@ fn claim_asset(account_addr: &str, dao_address : &str , voter_card_address : &st
     output
 }
 
-
-#[test]
-fn test_publish() {
-    reset_sim();
-    let _user = create_account();
-    let package_addr = publish_package(Some("."));
-    println!("User Package : {:?}", package_addr);
+fn claim_single_asset(account_addr: &str, dao_address : &str , voter_card_address : &str, asset_address : &str, amount : Option<&str>) -> String {
+    let amount = match amount
+    {
+        None => String::from("None"),
+        Some(amount) => format!("Some(Decimal(\"{}\"))", amount)
+    };
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/claim_asset.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("asset", asset_address)
+                             .env("amount", amount));
+    output
 }
 
+fn sweep_expired_claims(account_addr: &str, dao_address : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/sweep_expired_claims.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address));
+    output
+}
 
-
-#[test]
-fn test_instantiate() {
-    reset_sim();
-    let user = create_account();
-    let package_addr = publish_package(Some("."));
-    let dao = instantiate(&user.address, &package_addr);
-    println!("dao component : {:#?}", dao);
+fn make_funding_request(account_addr: &str, dao_address : &str, voter_card_address : &str, description : &str, resource : &str, amount : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/make_funding_request.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("description", description)
+                             .env("resource", resource)
+                             .env("amount", amount));
+    output
 }
 
-#[test]
-fn test_instantiate_custom() {
-    reset_sim();
-    let user = create_account();
-    let package_addr = publish_package(Some("."));
-    let admin_badge_addr = create_admin_badge();
-    let dao = instantiate_custom(&user.address, &package_addr, &admin_badge_addr );
-    let owned = dao.get_amount_owned(&user.address, &dao.styx_address);
-    assert_eq!(owned.unwrap(), dec!(100));
+fn stake_on_funding_request(account_addr: &str, dao_address : &str, voter_card_address : &str, request_id : &str, amount : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/stake_on_funding_request.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("request_id", request_id)
+                             .env("amount", amount));
+    output
 }
 
+fn update_funding_request(account_addr: &str, dao_address : &str, request_id : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/update_funding_request.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("request_id", request_id));
+    output
+}
 
-#[test]
-fn test_withdraw()
-{
-    reset_sim();
-    let user = create_account();
-    let package_addr = publish_package(Some("."));
-    let dao = instantiate(&user.address, &package_addr);
-    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
-    let user_owned_stx = user.get_amount_owned(&dao.styx_address).unwrap();
-    assert_eq!(user_owned_stx, dec!(10));
-    let dao_owned = dao.get_amount_owned(&user.address, &dao.styx_address);
-    assert_eq!(dao_owned.unwrap(), dec!(90));
+fn pause(account_addr: &str, dao_address : &str, guardian_badge_address : &str, group : &str, duration : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/pause.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("guardian_badge", guardian_badge_address)
+                             .env("group", group)
+                             .env("duration", duration));
+    output
 }
 
-#[test]
-fn test_mint_voter_card()
-{
-    reset_sim();
-    let user = create_account();
-    let package_addr = publish_package(Some("."));
-    let dao = instantiate(&user.address, &package_addr);
-    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
-    let mut owned_stx = user.get_amount_owned(&dao.styx_address).unwrap();
-    assert_eq!(owned_stx, dec!(10));
+fn veto_proposal(account_addr: &str, dao_address : &str , voter_card_address : &str, proposal_id : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/veto_proposal.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("proposal_id", proposal_id));
+    output
+}
 
-    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "5");
-    owned_stx =  user.get_amount_owned(&dao.styx_address).unwrap();
-    assert_eq!(owned_stx, dec!(5));
+fn set_default_account(account: &Account) {
+    run_command(Command::new("resim")
+                .arg("set-default-account")
+                .arg(&account.address)
+                .arg(&account._privkey));
 }
 
+fn create_voting_key(account_addr: &str, dao_address : &str, voter_card_address : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/create_voting_key.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address));
+    output
+}
 
-#[test]
-fn test_emit() {
-    reset_sim();
-    let user = create_account();
-    let package_addr = publish_package(Some("."));
-    let dao = instantiate(&user.address, &package_addr);
+fn revoke_voting_key(account_addr: &str, dao_address : &str, voter_card_address : &str, key_id : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/revoke_voting_key.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("key_id", key_id));
+    output
+}
 
-    // Withdraw all tokens from styx vault
-    withdraw(&user.address, &dao.address, &dao.external_admin_address, "100");
-    let mut dao_styx = dao.get_amount_owned(&user.address, &dao.styx_address).unwrap();
-    let owned_stx = user.get_amount_owned(&dao.styx_address).unwrap();
-    assert_eq!(dao_styx, dec!(0));
-    assert_eq!(owned_stx, dec!(100));
+fn support_proposal_with_key(account_addr: &str, dao_address : &str, voting_key_address : &str, proposal_id : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/support_proposal_with_key.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voting_key", voting_key_address)
+                             .env("proposal_id", proposal_id));
+    output
+}
 
-    // Now emit new tokens
-    emit(&user.address, &dao.address, &dao.external_admin_address, "1000");
-    dao_styx = dao.get_amount_owned(&user.address, &dao.styx_address).unwrap();
-    assert_eq!(dao_styx, dec!(1000));
+/// Calls "resim set-current-epoch"
+fn set_current_epoch(epoch: u64) {
+    run_command(Command::new("resim")
+        .arg("set-current-epoch")
+        .arg(epoch.to_string()));
+}
+
+fn make_proposal(account_addr: &str, dao_address : &str, voter_card_address : &str, title : &str, changes : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/make_proposal.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("title", title)
+                             .env("summary", title)
+                             .env("discussion_url", DISCUSSION_URL)
+                             .env("content_hash", CONTENT_HASH)
+                             .env("changes", changes)
+                             .env("fast_track", "false")
+                             .env("prerequisites", "Vec<U64>()"));
+    output
+}
+
+fn make_proposal_with_prerequisites(account_addr: &str, dao_address : &str, voter_card_address : &str, title : &str, changes : &str, prerequisites : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/make_proposal.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("title", title)
+                             .env("summary", title)
+                             .env("discussion_url", DISCUSSION_URL)
+                             .env("content_hash", CONTENT_HASH)
+                             .env("changes", changes)
+                             .env("fast_track", "false")
+                             .env("prerequisites", prerequisites));
+    output
+}
+
+fn amend_proposal(account_addr: &str, dao_address : &str, voter_card_address : &str, proposal_id : &str, title : &str, changes : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/amend_proposal.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("proposal_id", proposal_id)
+                             .env("title", title)
+                             .env("summary", title)
+                             .env("discussion_url", DISCUSSION_URL)
+                             .env("content_hash", CONTENT_HASH)
+                             .env("changes", changes));
+    output
+}
+
+fn suggest_amendment(account_addr: &str, dao_address : &str, voter_card_address : &str, proposal_id : &str, title : &str, changes : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/suggest_amendment.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("proposal_id", proposal_id)
+                             .env("title", title)
+                             .env("summary", title)
+                             .env("discussion_url", DISCUSSION_URL)
+                             .env("content_hash", CONTENT_HASH)
+                             .env("changes", changes));
+    output
+}
+
+fn support_amendment(account_addr: &str, dao_address : &str, voter_card_address : &str, proposal_id : &str, amendment_id : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/support_amendment.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("proposal_id", proposal_id)
+                             .env("amendment_id", amendment_id));
+    output
+}
+
+fn make_fast_track_proposal(account_addr: &str, dao_address : &str, voter_card_address : &str, title : &str, changes : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/make_proposal.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("title", title)
+                             .env("summary", title)
+                             .env("discussion_url", DISCUSSION_URL)
+                             .env("content_hash", CONTENT_HASH)
+                             .env("changes", changes)
+                             .env("fast_track", "true")
+                             .env("prerequisites", "Vec<U64>()"));
+    output
+}
+
+fn make_optimistic_proposal(account_addr: &str, dao_address : &str, voter_card_address : &str, title : &str, changes : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/make_optimistic_proposal.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("title", title)
+                             .env("summary", title)
+                             .env("discussion_url", DISCUSSION_URL)
+                             .env("content_hash", CONTENT_HASH)
+                             .env("changes", changes));
+    output
+}
+
+fn object_to_proposal(account_addr: &str, dao_address : &str , voter_card_address : &str, proposal_id : &str) -> String {
+    let output = run_command(Command::new("resim")
+                             .arg("run")
+                             .arg("rtm/object_to_proposal.rtm")
+                             .env("account", account_addr)
+                             .env("dao", &dao_address)
+                             .env("voter_card", voter_card_address)
+                             .env("proposal_id", proposal_id));
+    output
+}
+
+/// Makes a proposal go through its support and voting phases with the default periods.
+/// The proposal is supported by the given account and no vote is cast, which is enough for it to
+/// be accepted with the default thresholds.
+///
+/// The proposal is executed at epoch `current_epoch + 338`.
+///
+/// Returns the output of the transaction executing the proposal.
+fn pass_proposal(account_addr: &str, dao_address : &str, voter_card_address : &str, proposal_id : &str, current_epoch : u64) -> String {
+    support_proposal(account_addr, dao_address, voter_card_address, proposal_id);
+    set_current_epoch(current_epoch + 169);
+    advance_with_proposal(account_addr, dao_address, proposal_id);
+    set_current_epoch(current_epoch + 338);
+    advance_with_proposal(account_addr, dao_address, proposal_id)
+}
+
+#[test]
+fn test_publish() {
+    reset_sim();
+    let _user = create_account();
+    let package_addr = publish_package(Some("."));
+    println!("User Package : {:?}", package_addr);
+}
+
+
+
+#[test]
+fn test_instantiate() {
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    println!("dao component : {:#?}", dao);
+}
+
+#[test]
+fn test_instantiate_custom() {
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let admin_badge_addr = create_admin_badge();
+    let dao = instantiate_custom(&user.address, &package_addr, &admin_badge_addr );
+    let owned = dao.get_amount_owned(&user.address, &dao.styx_address);
+    assert_eq!(owned.unwrap(), dec!(100));
+}
+
+
+#[test]
+fn test_withdraw()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    let user_owned_stx = user.get_amount_owned(&dao.styx_address).unwrap();
+    assert_eq!(user_owned_stx, dec!(10));
+    let dao_owned = dao.get_amount_owned(&user.address, &dao.styx_address);
+    assert_eq!(dao_owned.unwrap(), dec!(90));
+}
+
+#[test]
+fn test_mint_voter_card()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    let mut owned_stx = user.get_amount_owned(&dao.styx_address).unwrap();
+    assert_eq!(owned_stx, dec!(10));
+
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "5");
+    owned_stx =  user.get_amount_owned(&dao.styx_address).unwrap();
+    assert_eq!(owned_stx, dec!(5));
+}
+
+
+#[test]
+fn test_emit() {
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+
+    // Withdraw all tokens from styx vault
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "100");
+    let mut dao_styx = dao.get_amount_owned(&user.address, &dao.styx_address).unwrap();
+    let owned_stx = user.get_amount_owned(&dao.styx_address).unwrap();
+    assert_eq!(dao_styx, dec!(0));
+    assert_eq!(owned_stx, dec!(100));
+
+    // Now emit new tokens
+    emit(&user.address, &dao.address, &dao.external_admin_address, "1000");
+    dao_styx = dao.get_amount_owned(&user.address, &dao.styx_address).unwrap();
+    assert_eq!(dao_styx, dec!(1000));
 }
 
 
@@ -643,7 +1044,7 @@ fn test_lock() {
     mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "5");
     lock(&user.address, &dao.address, &dao.voter_card_address, &dao.styx_address, "5");
     let locked = dao.get_locked_tokens(&user.address);
-    assert_eq!(locked, dec!(5));
+    assert_eq!(locked, dec!(10));
 }
 
 #[test]
@@ -709,4 +1110,1102 @@ fn test_untransferable_voter_card(){
     let transfer_output = simple_transfer(&user1.address, &user2.address, &dao.voter_card_address, "5");
     // Fails correctly
     println!("{}",transfer_output);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_suspended_voter()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "5");
+
+    // Tokens need to be locked for some time to have voting power
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Suspend voter 0",
+                  "Vec<Enum>(Enum(\"SuspendVoter\", 0u64, 100000u64))");
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    let result = std::panic::catch_unwind(|| {
+        lock(&user.address, &dao.address, &dao.voter_card_address, &dao.styx_address, "5");
+    });
+    assert!(result.is_err());
+    assert_eq!(user.get_amount_owned(&dao.styx_address).unwrap(), dec!(5));
+}
+
+#[test]
+fn test_slash_locked_tokens()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Slash voter 0",
+                  "Vec<Enum>(Enum(\"SlashLockedTokens\", 0u64, Decimal(\"4\")))");
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    assert_eq!(dao.get_locked_tokens(&user.address), dec!(6));
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(94));
+
+    // Only the remaining tokens can be unlocked
+    unlock_all(&user.address, &dao.address, &dao.voter_card_address);
+    assert_eq!(user.get_amount_owned(&dao.styx_address).unwrap(), dec!(6));
+
+    // A VoterCard that does not exist cannot be slashed
+    let result = std::panic::catch_unwind(|| {
+        make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Slash voter 1",
+                      "Vec<Enum>(Enum(\"SlashLockedTokens\", 1u64, Decimal(\"4\")))");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_mint_voter_card_gated_membership()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let admin_badge_addr = create_admin_badge();
+    let membership_badge_addr = create_admin_badge();
+    let dao = instantiate_from_manifest(&user.address, &package_addr, &admin_badge_addr, "None",
+                                        &format!("Some(ResourceAddress(\"{}\"))", membership_badge_addr), "None");
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+
+    // Minting without a proof of the membership badge fails
+    let result = std::panic::catch_unwind(|| {
+        mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "5");
+    });
+    assert!(result.is_err());
+    assert_eq!(user.get_amount_owned(&dao.styx_address).unwrap(), dec!(10));
+
+    mint_voter_card_with_membership(&user.address, &dao.address, &dao.styx_address, &membership_badge_addr, "5");
+    assert_eq!(user.get_amount_owned(&dao.styx_address).unwrap(), dec!(5));
+    assert_eq!(user.get_amount_owned(&dao.voter_card_address).unwrap(), dec!(1));
+}
+
+#[test]
+fn test_change_membership_badge()
+{
+    reset_sim();
+    let user1 = create_account();
+    let user2 = create_account();
+    let package_addr = publish_package(Some("."));
+    let membership_badge_addr = create_admin_badge();
+    let dao = instantiate(&user1.address, &package_addr);
+    withdraw(&user1.address, &dao.address, &dao.external_admin_address, "20");
+    mint_voter_card_with_bucket(&user1.address, &dao.address, &dao.styx_address, "10");
+    simple_transfer(&user1.address, &user2.address, &dao.styx_address, "10");
+    simple_transfer(&user1.address, &user2.address, &membership_badge_addr, "1");
+
+    set_current_epoch(2016);
+    make_proposal(&user1.address, &dao.address, &dao.voter_card_address, "Gate the membership",
+                  &format!("Vec<Enum>(Enum(\"ChangeMembershipBadge\", Some(ResourceAddress(\"{}\"))))", membership_badge_addr));
+    pass_proposal(&user1.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    // Once gated, minting requires a proof of the membership badge
+    set_default_account(&user2);
+    let result = std::panic::catch_unwind(|| {
+        mint_voter_card_with_bucket(&user2.address, &dao.address, &dao.styx_address, "2");
+    });
+    assert!(result.is_err());
+    mint_voter_card_with_membership(&user2.address, &dao.address, &dao.styx_address, &membership_badge_addr, "2");
+    assert_eq!(user2.get_amount_owned(&dao.voter_card_address).unwrap(), dec!(1));
+    set_default_account(&user1);
+
+    make_proposal(&user1.address, &dao.address, &dao.voter_card_address, "Open the membership",
+                  "Vec<Enum>(Enum(\"ChangeMembershipBadge\", None))");
+    pass_proposal(&user1.address, &dao.address, &dao.voter_card_address, "1", 2016 + 338);
+
+    // Once open again, anyone can mint a VoterCard
+    set_default_account(&user2);
+    simple_transfer(&user2.address, &user1.address, &membership_badge_addr, "1");
+    mint_voter_card_with_bucket(&user2.address, &dao.address, &dao.styx_address, "2");
+    assert_eq!(user2.get_amount_owned(&dao.voter_card_address).unwrap(), dec!(2));
+    set_default_account(&user1);
+}
+
+#[test]
+fn test_voting_key()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "5");
+    create_voting_key(&user.address, &dao.address, &dao.voter_card_address);
+    assert_eq!(user.get_amount_owned(&dao.voting_key_address).unwrap(), dec!(1));
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "First proposal",
+                  "Vec<Enum>(Enum(\"ChangeVotePeriod\", 100u64))");
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Second proposal",
+                  "Vec<Enum>(Enum(\"ChangeVotePeriod\", 200u64))");
+
+    support_proposal_with_key(&user.address, &dao.address, &dao.voting_key_address, "0");
+
+    // Once revoked, the key cannot be used anymore
+    revoke_voting_key(&user.address, &dao.address, &dao.voter_card_address, "0");
+    let result = std::panic::catch_unwind(|| {
+        support_proposal_with_key(&user.address, &dao.address, &dao.voting_key_address, "1");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_emit_max_supply()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+
+    let result = std::panic::catch_unwind(|| {
+        emit(&user.address, &dao.address, &dao.external_admin_address, "9901");
+    });
+    assert!(result.is_err());
+
+    emit(&user.address, &dao.address, &dao.external_admin_address, "9900");
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(10000));
+}
+
+#[test]
+fn test_emission_schedule_capped()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let admin_badge_addr = create_admin_badge();
+    let dao = instantiate_from_manifest(&user.address, &package_addr, &admin_badge_addr,
+                                        "Some(Enum(\"Halving\", Decimal(\"1000\"), 1000u64))", "None", "None");
+
+    set_current_epoch(5);
+    trigger_emission(&user.address, &dao.address);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(5100));
+
+    // The schedule would emit far more than the max supply by now
+    set_current_epoch(1000000);
+    trigger_emission(&user.address, &dao.address);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(10000));
+
+    let result = std::panic::catch_unwind(|| {
+        emit(&user.address, &dao.address, &dao.external_admin_address, "1");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_proposal_over_max_supply()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    // The execution fails instead of minting over the max supply
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Mint over the max supply",
+                  "Vec<Enum>(Enum(\"AllowMinting\", Decimal(\"9901\")))");
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(90));
+
+    // An invalid emission schedule is rejected when the proposal is made
+    let result = std::panic::catch_unwind(|| {
+        make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Emit with a zero halving period",
+                      "Vec<Enum>(Enum(\"ChangeEmissionSchedule\", Some(Enum(\"Halving\", Decimal(\"10\"), 0u64))))");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_burn_and_circulating_supply()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    assert_eq!(dao.get_circulating_supply(&user.address), dec!(0));
+
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "30");
+    burn(&user.address, &dao.address, &dao.styx_address, "10");
+    assert_eq!(user.get_amount_owned(&dao.styx_address).unwrap(), dec!(20));
+    assert_eq!(dao.get_circulating_supply(&user.address), dec!(20));
+
+    // Burning treasury tokens does not change the circulating supply
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "20");
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Burn treasury tokens",
+                  "Vec<Enum>(Enum(\"BurnTreasuryTokens\", Decimal(\"50\")))");
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(20));
+    assert_eq!(dao.get_circulating_supply(&user.address), dec!(20));
+
+    // Burning more than the treasury owns burns all the treasury tokens
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Burn more than the treasury",
+                  "Vec<Enum>(Enum(\"BurnTreasuryTokens\", Decimal(\"50\")))");
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "1", 2016 + 338);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(0));
+    assert_eq!(dao.get_circulating_supply(&user.address), dec!(20));
+}
+
+#[test]
+fn test_circulating_supply_with_claims()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "30");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "30");
+    assert_eq!(dao.get_circulating_supply(&user.address), dec!(30));
+
+    // Tokens granted by a proposal only enter circulation when they are claimed
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Grant tokens",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"10\"), 0u64))",
+                           dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+    assert_eq!(dao.get_circulating_supply(&user.address), dec!(30));
+
+    claim_asset(&user.address, &dao.address, &dao.voter_card_address);
+    assert_eq!(dao.get_circulating_supply(&user.address), dec!(40));
+}
+
+#[test]
+fn test_external_admin_expiry()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let admin_badge_addr = create_admin_badge();
+    let dao = instantiate_from_manifest(&user.address, &package_addr, &admin_badge_addr, "None", "None", "Some(10u64)");
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+
+    set_current_epoch(10);
+    let result = std::panic::catch_unwind(|| {
+        withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    });
+    assert!(result.is_err());
+    assert_eq!(user.get_amount_owned(&dao.styx_address).unwrap(), dec!(10));
+}
+
+#[test]
+fn test_revoke_external_admin()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Revoke the external admin",
+                  "Vec<Enum>(Enum(\"RevokeExternalAdmin\"))");
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    let result = std::panic::catch_unwind(|| {
+        emit(&user.address, &dao.address, &dao.external_admin_address, "10");
+    });
+    assert!(result.is_err());
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(90));
+
+    // The DAO can still mint through proposals
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Mint after the revocation",
+                  "Vec<Enum>(Enum(\"AllowMinting\", Decimal(\"10\")))");
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "1", 2016 + 338);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(100));
+}
+
+#[test]
+fn test_update_token_metadata()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Add a url",
+                  "Vec<Enum>(Enum(\"UpdateTokenMetadata\", \"url\", \"https://styx.example\"), Enum(\"UpdateVoterCardMetadata\", \"name\", \"Styx VoterCard\"))");
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    let styx_output = run_command(Command::new("resim").arg("show").arg(&dao.styx_address));
+    assert!(styx_output.contains("https://styx.example"));
+    let voter_card_output = run_command(Command::new("resim").arg("show").arg(&dao.voter_card_address));
+    assert!(voter_card_output.contains("Styx VoterCard"));
+}
+
+#[test]
+fn test_create_resource()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Create a contributor token",
+                  "Vec<Enum>(Enum(\"CreateResource\", Struct(\"Contributor\", \"CTB\", Enum(\"Fungible\", 18u8), Decimal(\"1000\"))))");
+    let output = pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    lazy_static! {
+        static ref RE_RESOURCE: Regex = Regex::new(r"New resource created: (\w*)").unwrap();
+    }
+    let resource_address = RE_RESOURCE.captures(&output).expect("Failed to parse new resource address")[1].to_string();
+    assert_eq!(dao.get_amount_owned(&user.address, &resource_address).unwrap(), dec!(1000));
+
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Mint and burn contributor tokens",
+                  &format!("Vec<Enum>(Enum(\"MintResource\", ResourceAddress(\"{0}\"), Decimal(\"500\")), Enum(\"BurnResource\", ResourceAddress(\"{0}\"), Decimal(\"200\")))", resource_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "1", 2016 + 338);
+    assert_eq!(dao.get_amount_owned(&user.address, &resource_address).unwrap(), dec!(1300));
+
+    // Resources that cannot be created are rejected when the proposal is made
+    let result = std::panic::catch_unwind(|| {
+        make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Create a token with a too high divisibility",
+                      "Vec<Enum>(Enum(\"CreateResource\", Struct(\"Contributor\", \"CTB\", Enum(\"Fungible\", 19u8), Decimal(\"1000\"))))");
+    });
+    assert!(result.is_err());
+
+    let result = std::panic::catch_unwind(|| {
+        make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Create half an item",
+                      "Vec<Enum>(Enum(\"CreateResource\", Struct(\"Badge\", \"BDG\", Enum(\"NonFungible\"), Decimal(\"0.5\"))))");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_call_method()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let mock_package_addr = publish_package(Some("tests/mock_protocol"));
+    let dao = instantiate(&user.address, &package_addr);
+    let (mock_address, reward_address) = instantiate_mock_protocol(&user.address, &mock_package_addr);
+    gift_asset(&user.address, &dao.address, "100", RADIX_TOKEN);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    let arguments = manifest_bytes(&args!(dec!(5)));
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Deposit in the mock protocol",
+                  &format!("Vec<Enum>(Enum(\"CallMethod\", ComponentAddress(\"{}\"), \"deposit\", {}, Vec<Tuple>(Tuple(ResourceAddress(\"{}\"), Decimal(\"10\"))), Enum(\"Buckets\")))",
+                           mock_address, arguments, RADIX_TOKEN));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    assert_eq!(dao.get_amount_owned(&user.address, RADIX_TOKEN).unwrap(), dec!(90));
+    assert_eq!(dao.get_amount_owned(&user.address, &reward_address).unwrap(), dec!(5));
+
+    // Methods returning nothing can be called as well
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Donate to the mock protocol",
+                  &format!("Vec<Enum>(Enum(\"CallMethod\", ComponentAddress(\"{}\"), \"donate\", {}, Vec<Tuple>(Tuple(ResourceAddress(\"{}\"), Decimal(\"20\"))), Enum(\"Nothing\")))",
+                           mock_address, manifest_bytes(&args!()), RADIX_TOKEN));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "1", 2016 + 338);
+
+    assert_eq!(dao.get_amount_owned(&user.address, RADIX_TOKEN).unwrap(), dec!(70));
+    assert_eq!(dao.get_amount_owned(&user.address, &reward_address).unwrap(), dec!(5));
+
+    // A call that keeps reverting can be marked as failed after the execution grace period
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Donate with a wrong return kind",
+                  &format!("Vec<Enum>(Enum(\"CallMethod\", ComponentAddress(\"{}\"), \"donate\", {}, Vec<Tuple>(Tuple(ResourceAddress(\"{}\"), Decimal(\"20\"))), Enum(\"Bucket\")))",
+                           mock_address, manifest_bytes(&args!()), RADIX_TOKEN));
+    let result = std::panic::catch_unwind(|| {
+        pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "2", 2016 + 338);
+    });
+    assert!(result.is_err());
+
+    let result = std::panic::catch_unwind(|| {
+        fail_stuck_proposal(&user.address, &dao.address, "2");
+    });
+    assert!(result.is_err());
+
+    set_current_epoch(2016 + 676 + 168);
+    fail_stuck_proposal(&user.address, &dao.address, "2");
+    assert_eq!(dao.get_amount_owned(&user.address, RADIX_TOKEN).unwrap(), dec!(70));
+
+    // The DAO cannot call its own methods, which would use its internal badge
+    let result = std::panic::catch_unwind(|| {
+        make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Withdraw from the DAO",
+                      &format!("Vec<Enum>(Enum(\"CallMethod\", ComponentAddress(\"{}\"), \"withdraw\", {}, Vec<Tuple>(), Enum(\"Bucket\")))",
+                               dao.address, manifest_bytes(&args!(dec!(10)))));
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_transfer_non_fungibles()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Create contributor badges",
+                  "Vec<Enum>(Enum(\"CreateResource\", Struct(\"Contributor badge\", \"CBG\", Enum(\"NonFungible\"), Decimal(\"3\"))))");
+    let output = pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    lazy_static! {
+        static ref RE_RESOURCE: Regex = Regex::new(r"New resource created: (\w*)").unwrap();
+    }
+    let resource_address = RE_RESOURCE.captures(&output).expect("Failed to parse new resource address")[1].to_string();
+    assert_eq!(dao.get_amount_owned(&user.address, &resource_address).unwrap(), dec!(3));
+
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Give a badge to voter 0",
+                  &format!("Vec<Enum>(Enum(\"TransferNonFungibles\", ResourceAddress(\"{}\"), Vec<NonFungibleId>(NonFungibleId(\"{}\")), 0u64))",
+                           resource_address, NonFungibleId::from_u64(1)));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "1", 2016 + 338);
+    assert_eq!(dao.get_amount_owned(&user.address, &resource_address).unwrap(), dec!(2));
+
+    claim_asset(&user.address, &dao.address, &dao.voter_card_address);
+    assert_eq!(user.get_amount_owned(&resource_address).unwrap(), dec!(1));
+
+    // The same item cannot be listed twice
+    let result = std::panic::catch_unwind(|| {
+        make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Give a badge twice",
+                      &format!("Vec<Enum>(Enum(\"TransferNonFungibles\", ResourceAddress(\"{0}\"), Vec<NonFungibleId>(NonFungibleId(\"{1}\"), NonFungibleId(\"{1}\")), 0u64))",
+                               resource_address, NonFungibleId::from_u64(2)));
+    });
+    assert!(result.is_err());
+
+    // Transferring an item that the DAO does not own anymore fails the execution
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Give the same badge again",
+                  &format!("Vec<Enum>(Enum(\"TransferNonFungibles\", ResourceAddress(\"{}\"), Vec<NonFungibleId>(NonFungibleId(\"{}\")), 0u64))",
+                           resource_address, NonFungibleId::from_u64(1)));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "2", 2016 + 2 * 338);
+    assert_eq!(dao.get_amount_owned(&user.address, &resource_address).unwrap(), dec!(2));
+    let result = std::panic::catch_unwind(|| {
+        advance_with_proposal(&user.address, &dao.address, "2");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_pay_account()
+{
+    reset_sim();
+    let user = create_account();
+    let vendor = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    gift_asset(&user.address, &dao.address, "100", RADIX_TOKEN);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+    let vendor_balance = vendor.get_amount_owned(RADIX_TOKEN).unwrap();
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pay the vendor",
+                  &format!("Vec<Enum>(Enum(\"PayAccount\", ResourceAddress(\"{}\"), Decimal(\"40\"), ComponentAddress(\"{}\")))",
+                           RADIX_TOKEN, vendor.address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    assert_eq!(dao.get_amount_owned(&user.address, RADIX_TOKEN).unwrap(), dec!(60));
+    assert_eq!(vendor.get_amount_owned(RADIX_TOKEN).unwrap(), vendor_balance + dec!(40));
+
+    // Payments to components that are not accounts are rejected
+    let result = std::panic::catch_unwind(|| {
+        make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pay the DAO",
+                      &format!("Vec<Enum>(Enum(\"PayAccount\", ResourceAddress(\"{}\"), Decimal(\"10\"), ComponentAddress(\"{}\")))",
+                               RADIX_TOKEN, dao.address));
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_claims_sharing_one_vault()
+{
+    reset_sim();
+    let user1 = create_account();
+    let user2 = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user1.address, &package_addr);
+    gift_asset(&user1.address, &dao.address, "100", RADIX_TOKEN);
+    withdraw(&user1.address, &dao.address, &dao.external_admin_address, "15");
+    mint_voter_card_with_bucket(&user1.address, &dao.address, &dao.styx_address, "10");
+    simple_transfer(&user1.address, &user2.address, &dao.styx_address, "5");
+    set_default_account(&user2);
+    mint_voter_card_with_bucket(&user2.address, &dao.address, &dao.styx_address, "5");
+    set_default_account(&user1);
+
+    set_current_epoch(2016);
+    make_proposal(&user1.address, &dao.address, &dao.voter_card_address, "Pay two members",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{0}\"), Decimal(\"30\"), 0u64), Enum(\"AllowSpending\", ResourceAddress(\"{0}\"), Decimal(\"50\"), 1u64))",
+                           RADIX_TOKEN));
+    pass_proposal(&user1.address, &dao.address, &dao.voter_card_address, "0", 2016);
+    assert_eq!(dao.get_amount_owned(&user1.address, RADIX_TOKEN).unwrap(), dec!(20));
+
+    // Partial claim of a single resource
+    claim_single_asset(&user1.address, &dao.address, &dao.voter_card_address, RADIX_TOKEN, Some("10"));
+    assert_eq!(dao.get_amount_owned(&user1.address, RADIX_TOKEN).unwrap(), dec!(20));
+
+    // The second claimant takes exactly what it was granted
+    set_default_account(&user2);
+    claim_asset(&user2.address, &dao.address, &dao.voter_card_address);
+    set_default_account(&user1);
+    assert_eq!(dao.get_amount_owned(&user1.address, RADIX_TOKEN).unwrap(), dec!(20));
+
+    // The first claimant can still claim the rest of its grant from the same vault
+    claim_single_asset(&user1.address, &dao.address, &dao.voter_card_address, RADIX_TOKEN, None);
+    assert_eq!(dao.get_amount_owned(&user1.address, RADIX_TOKEN).unwrap(), dec!(20));
+}
+
+#[test]
+fn test_claim_more_than_granted()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    gift_asset(&user.address, &dao.address, "100", RADIX_TOKEN);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pay a member",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"30\"), 0u64))", RADIX_TOKEN));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    let result = std::panic::catch_unwind(|| {
+        claim_single_asset(&user.address, &dao.address, &dao.voter_card_address, RADIX_TOKEN, Some("31"));
+    });
+    assert!(result.is_err());
+
+    claim_asset(&user.address, &dao.address, &dao.voter_card_address);
+    assert_eq!(dao.get_amount_owned(&user.address, RADIX_TOKEN).unwrap(), dec!(70));
+}
+
+#[test]
+fn test_grant_over_free_balance()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    // The same 90 tokens cannot be promised twice: the execution fails and no grant is made
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pay a member twice",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{0}\"), Decimal(\"60\"), 0u64), Enum(\"AllowSpending\", ResourceAddress(\"{0}\"), Decimal(\"60\"), 0u64))", dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(90));
+
+    let result = std::panic::catch_unwind(|| {
+        claim_single_asset(&user.address, &dao.address, &dao.voter_card_address, &dao.styx_address, None);
+    });
+    assert!(result.is_err());
+
+    // The proposal cannot be executed again
+    let result = std::panic::catch_unwind(|| {
+        advance_with_proposal(&user.address, &dao.address, "0");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_sweep_expired_claims()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pay a member with a short claim period",
+                  &format!("Vec<Enum>(Enum(\"ChangeClaimPeriod\", 100u64), Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"30\"), 0u64))", dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(60));
+
+    // The grant expires 100 epochs after the execution of the proposal
+    set_current_epoch(2016 + 338 + 101);
+    sweep_expired_claims(&user.address, &dao.address);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(90));
+
+    let result = std::panic::catch_unwind(|| {
+        claim_single_asset(&user.address, &dao.address, &dao.voter_card_address, &dao.styx_address, None);
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_outflow_limit()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Limit spending and pay a member",
+                  &format!("Vec<Enum>(Enum(\"ChangeOutflowLimit\", ResourceAddress(\"{0}\"), Some(Tuple(Decimal(\"50\"), 1000u64))), Enum(\"AllowSpending\", ResourceAddress(\"{0}\"), Decimal(\"40\"), 0u64))",
+                           dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(50));
+
+    // Going over the limit of the window makes the execution fail without spending anything
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pay a member again",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"20\"), 0u64))", dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "1", 2016 + 338);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(50));
+
+    let result = std::panic::catch_unwind(|| {
+        advance_with_proposal(&user.address, &dao.address, "1");
+    });
+    assert!(result.is_err());
+
+    // A new proposal can be executed once the first outflow left the window
+    set_current_epoch(2016 + 338 + 1000);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pay a member later",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"20\"), 0u64))", dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "2", 2016 + 338 + 1000);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(30));
+
+    // A limit with a zero epoch window is rejected when the proposal is made
+    let result = std::panic::catch_unwind(|| {
+        make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Break the limit",
+                      &format!("Vec<Enum>(Enum(\"ChangeOutflowLimit\", ResourceAddress(\"{}\"), Some(Tuple(Decimal(\"50\"), 0u64))))",
+                               dao.styx_address));
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_guardian_pause()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    let guardian_badge = create_admin_badge();
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Appoint guardians and pay a member",
+                  &format!("Vec<Enum>(Enum(\"ChangeGuardianBadge\", Some(ResourceAddress(\"{}\"))), Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"30\"), 0u64))",
+                           guardian_badge, dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    // Guardians cannot pause for more than the max guardian pause
+    let result = std::panic::catch_unwind(|| {
+        pause(&user.address, &dao.address, &guardian_badge, "Claims", "169");
+    });
+    assert!(result.is_err());
+
+    pause(&user.address, &dao.address, &guardian_badge, "Claims", "168");
+    let result = std::panic::catch_unwind(|| {
+        claim_single_asset(&user.address, &dao.address, &dao.voter_card_address, &dao.styx_address, Some("10"));
+    });
+    assert!(result.is_err());
+
+    // The pause ends automatically
+    set_current_epoch(2016 + 338 + 168);
+    claim_single_asset(&user.address, &dao.address, &dao.voter_card_address, &dao.styx_address, Some("10"));
+    assert_eq!(user.get_amount_owned(&dao.styx_address).unwrap(), dec!(10));
+
+    // Only a proposal can pause for longer
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pause the claims",
+                  "Vec<Enum>(Enum(\"SetPause\", Enum(\"Claims\"), 5000u64))");
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "1", 2016 + 338 + 168);
+    let result = std::panic::catch_unwind(|| {
+        claim_single_asset(&user.address, &dao.address, &dao.voter_card_address, &dao.styx_address, None);
+    });
+    assert!(result.is_err());
+
+    set_current_epoch(5000);
+    claim_single_asset(&user.address, &dao.address, &dao.voter_card_address, &dao.styx_address, None);
+    assert_eq!(user.get_amount_owned(&dao.styx_address).unwrap(), dec!(30));
+}
+
+#[test]
+fn test_veto_council()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Elect a veto council",
+                  "Vec<Enum>(Enum(\"ElectVetoCouncil\", Vec<U64>(0u64)))");
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    // Accepted proposals now wait for the end of the veto period
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pay a member",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"30\"), 0u64))", dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "1", 2016 + 338);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(90));
+
+    veto_proposal(&user.address, &dao.address, &dao.voter_card_address, "1");
+    set_current_epoch(2016 + 2 * 338 + 48);
+    let result = std::panic::catch_unwind(|| {
+        advance_with_proposal(&user.address, &dao.address, "1");
+    });
+    assert!(result.is_err());
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(90));
+
+    // A proposal that is not vetoed is executed after the veto period
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pay a member again",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"20\"), 0u64))", dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "2", 2016 + 2 * 338 + 48);
+    set_current_epoch(2016 + 3 * 338 + 2 * 48);
+    advance_with_proposal(&user.address, &dao.address, "2");
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(70));
+}
+
+#[test]
+fn test_optimistic_proposal()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Allow small optimistic payouts",
+                  &format!("Vec<Enum>(Enum(\"ChangeOptimisticLimit\", ResourceAddress(\"{}\"), Some(Decimal(\"50\"))))", dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    // Payouts over the limit cannot be optimistic
+    let result = std::panic::catch_unwind(|| {
+        make_optimistic_proposal(&user.address, &dao.address, &dao.voter_card_address, "Big payout",
+                                 &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"60\"), 0u64))", dao.styx_address));
+    });
+    assert!(result.is_err());
+
+    // A payout nobody objects to is accepted after the challenge period
+    make_optimistic_proposal(&user.address, &dao.address, &dao.voter_card_address, "Small payout",
+                             &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"30\"), 0u64))", dao.styx_address));
+    set_current_epoch(2016 + 338 + 72);
+    advance_with_proposal(&user.address, &dao.address, "1");
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(60));
+
+    // A payout with enough objections goes to a vote
+    make_optimistic_proposal(&user.address, &dao.address, &dao.voter_card_address, "Another small payout",
+                             &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"20\"), 0u64))", dao.styx_address));
+    object_to_proposal(&user.address, &dao.address, &dao.voter_card_address, "2");
+    set_current_epoch(2016 + 338 + 2 * 72);
+    let result = std::panic::catch_unwind(|| {
+        advance_with_proposal(&user.address, &dao.address, "2");
+    });
+    assert!(result.is_err());
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(60));
+}
+
+#[test]
+fn test_fast_track_proposal()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "50");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "50");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pay a member",
+                  &format!("Vec<Enum>(Enum(\"ChangeOutflowLimit\", ResourceAddress(\"{0}\"), Some(Tuple(Decimal(\"100\"), 1000u64))), Enum(\"AllowSpending\", ResourceAddress(\"{0}\"), Decimal(\"30\"), 0u64))",
+                           dao.styx_address));
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+
+    // Only pauses and tighter outflow limits can be fast-tracked
+    let result = std::panic::catch_unwind(|| {
+        make_fast_track_proposal(&user.address, &dao.address, &dao.voter_card_address, "Mint tokens",
+                                 "Vec<Enum>(Enum(\"AllowMinting\", Decimal(\"10\")))");
+    });
+    assert!(result.is_err());
+
+    let result = std::panic::catch_unwind(|| {
+        make_fast_track_proposal(&user.address, &dao.address, &dao.voter_card_address, "Lower the quorum",
+                                 "Vec<Enum>(Enum(\"ChangeMinimumVoteThreshold\", Decimal(\"0\")))");
+    });
+    assert!(result.is_err());
+
+    let result = std::panic::catch_unwind(|| {
+        make_fast_track_proposal(&user.address, &dao.address, &dao.voter_card_address, "Raise the limit",
+                                 &format!("Vec<Enum>(Enum(\"ChangeOutflowLimit\", ResourceAddress(\"{}\"), Some(Tuple(Decimal(\"200\"), 1000u64))))",
+                                          dao.styx_address));
+    });
+    assert!(result.is_err());
+
+    // A fast-track proposal is voted on directly and executed after a short voting phase. The
+    // voting power of the VoterCard reaches the default quorum
+    make_fast_track_proposal(&user.address, &dao.address, &dao.voter_card_address, "Pause the claims",
+                             "Vec<Enum>(Enum(\"SetPause\", Enum(\"Claims\"), 5000u64))");
+    vote_for_proposal(&user.address, &dao.address, &dao.voter_card_address, "1", "For");
+    set_current_epoch(2016 + 338 + 24);
+    advance_with_proposal(&user.address, &dao.address, "1");
+
+    let result = std::panic::catch_unwind(|| {
+        claim_asset(&user.address, &dao.address, &dao.voter_card_address);
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_ranked_choice_proposal()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_multiple_choice_proposal(&user.address, &dao.address, &dao.voter_card_address, "Choose a grant",
+                                  &format!("Vec<Struct>(Struct(\"Small grant\", Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{0}\"), Decimal(\"10\"), 0u64))), Struct(\"Big grant\", Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{0}\"), Decimal(\"20\"), 0u64))))",
+                                           dao.styx_address),
+                                  "RankedChoice");
+    support_proposal(&user.address, &dao.address, &dao.voter_card_address, "0");
+    set_current_epoch(2016 + 169);
+    advance_with_proposal(&user.address, &dao.address, "0");
+
+    // A yes/no vote is refused
+    let result = std::panic::catch_unwind(|| {
+        vote_for_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", "For");
+    });
+    assert!(result.is_err());
+
+    vote_for_options(&user.address, &dao.address, &dao.voter_card_address, "0", "1u64, 0u64");
+    set_current_epoch(2016 + 338);
+    advance_with_proposal(&user.address, &dao.address, "0");
+
+    // Only the changes of the winning option are executed
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(70));
+}
+
+#[test]
+fn test_poll()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_poll(&user.address, &dao.address, &dao.voter_card_address, "Should we apply for the grants program?", "Vec<String>(\"Yes\", \"No\", \"Later\")");
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Mint", "Vec<Enum>(Enum(\"AllowMinting\", Decimal(\"10\")))");
+    vote_for_poll(&user.address, &dao.address, &dao.voter_card_address, "0", "2");
+
+    // Voting for the poll does not prevent voting for a real proposal
+    support_proposal(&user.address, &dao.address, &dao.voter_card_address, "0");
+    set_current_epoch(2016 + 169);
+    advance_with_proposal(&user.address, &dao.address, "0");
+    vote_for_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", "For");
+
+    // A member can only vote once for a poll
+    let result = std::panic::catch_unwind(|| {
+        vote_for_poll(&user.address, &dao.address, &dao.voter_card_address, "0", "1");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_conviction_voting()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "50");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "50");
+
+    set_current_epoch(2016);
+    make_funding_request(&user.address, &dao.address, &dao.voter_card_address, "Small grant", &dao.styx_address, "2");
+    make_funding_request(&user.address, &dao.address, &dao.voter_card_address, "Large grant", &dao.styx_address, "8");
+
+    // Cannot stake more than the voting power of the VoterCard
+    let result = std::panic::catch_unwind(|| {
+        stake_on_funding_request(&user.address, &dao.address, &dao.voter_card_address, "0", "100");
+    });
+    assert!(result.is_err());
+
+    stake_on_funding_request(&user.address, &dao.address, &dao.voter_card_address, "0", "20");
+
+    // The conviction has not passed the threshold yet
+    set_current_epoch(2016 + 10);
+    update_funding_request(&user.address, &dao.address, "0");
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(50));
+
+    set_current_epoch(2016 + 100);
+    update_funding_request(&user.address, &dao.address, "0");
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(48));
+
+    // The grant is paid through the claims
+    claim_single_asset(&user.address, &dao.address, &dao.voter_card_address, &dao.styx_address, None);
+    assert_eq!(user.get_amount_owned(&dao.styx_address).unwrap(), dec!(2));
+
+    // The same stake never passes a request for a larger share of the treasury
+    stake_on_funding_request(&user.address, &dao.address, &dao.voter_card_address, "1", "20");
+    set_current_epoch(2016 + 2000);
+    update_funding_request(&user.address, &dao.address, "1");
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(48));
+}
+
+#[test]
+fn test_proposal_prerequisites()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "First grant",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"10\"), 0u64))", dao.styx_address));
+    make_proposal_with_prerequisites(&user.address, &dao.address, &dao.voter_card_address, "Second grant",
+                                     &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"20\"), 0u64))", dao.styx_address),
+                                     "Vec<U64>(0u64)");
+    support_proposal(&user.address, &dao.address, &dao.voter_card_address, "0");
+    support_proposal(&user.address, &dao.address, &dao.voter_card_address, "1");
+    set_current_epoch(2016 + 169);
+    advance_with_proposal(&user.address, &dao.address, "0");
+    advance_with_proposal(&user.address, &dao.address, "1");
+
+    // The second proposal cannot be executed before the first one
+    set_current_epoch(2016 + 338);
+    let result = std::panic::catch_unwind(|| {
+        advance_with_proposal(&user.address, &dao.address, "1");
+    });
+    assert!(result.is_err());
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(90));
+
+    advance_with_proposal(&user.address, &dao.address, "0");
+    advance_with_proposal(&user.address, &dao.address, "1");
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(60));
+}
+
+#[test]
+fn test_failed_prerequisite()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Unsupported grant",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"10\"), 0u64))", dao.styx_address));
+    make_proposal_with_prerequisites(&user.address, &dao.address, &dao.voter_card_address, "Follow-up grant",
+                                     &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"20\"), 0u64))", dao.styx_address),
+                                     "Vec<U64>(0u64)");
+    support_proposal(&user.address, &dao.address, &dao.voter_card_address, "1");
+
+    // The first proposal is rejected for lack of support, so the second one is rejected too
+    set_current_epoch(2016 + 169);
+    advance_with_proposal(&user.address, &dao.address, "0");
+    advance_with_proposal(&user.address, &dao.address, "1");
+    let result = std::panic::catch_unwind(|| {
+        advance_with_proposal(&user.address, &dao.address, "1");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_amendments()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    set_current_epoch(2016);
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, "Grant",
+                  &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"10\"), 0u64))", dao.styx_address));
+    support_proposal(&user.address, &dao.address, &dao.voter_card_address, "0");
+
+    // The author revises the proposal
+    amend_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", "Bigger grant",
+                   &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"20\"), 0u64))", dao.styx_address));
+
+    // An amendment with enough support revises it again
+    suggest_amendment(&user.address, &dao.address, &dao.voter_card_address, "0", "Smaller grant",
+                      &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"5\"), 0u64))", dao.styx_address));
+    support_amendment(&user.address, &dao.address, &dao.voter_card_address, "0", "0");
+
+    // The support was reset by the amendments so the proposal is supported again before the vote
+    pass_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", 2016);
+    assert_eq!(dao.get_amount_owned(&user.address, &dao.styx_address).unwrap(), dec!(85));
+
+    // A proposal cannot be amended after its suggestion phase
+    let result = std::panic::catch_unwind(|| {
+        amend_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", "Grant",
+                       &format!("Vec<Enum>(Enum(\"AllowSpending\", ResourceAddress(\"{}\"), Decimal(\"10\"), 0u64))", dao.styx_address));
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_proposal_metadata_length()
+{
+    reset_sim();
+    let user = create_account();
+    let package_addr = publish_package(Some("."));
+    let dao = instantiate(&user.address, &package_addr);
+    withdraw(&user.address, &dao.address, &dao.external_admin_address, "10");
+    mint_voter_card_with_bucket(&user.address, &dao.address, &dao.styx_address, "10");
+
+    make_proposal(&user.address, &dao.address, &dao.voter_card_address, &"a".repeat(100),
+                  "Vec<Enum>(Enum(\"AllowMinting\", Decimal(\"10\")))");
+
+    // A title longer than 100 bytes is refused
+    let result = std::panic::catch_unwind(|| {
+        make_proposal(&user.address, &dao.address, &dao.voter_card_address, &"a".repeat(101),
+                      "Vec<Enum>(Enum(\"AllowMinting\", Decimal(\"10\")))");
+    });
+    assert!(result.is_err());
+
+    // The metadata of amendments and of the other kinds of proposals is checked as well
+    let result = std::panic::catch_unwind(|| {
+        amend_proposal(&user.address, &dao.address, &dao.voter_card_address, "0", &"a".repeat(101),
+                       "Vec<Enum>(Enum(\"AllowMinting\", Decimal(\"5\")))");
+    });
+    assert!(result.is_err());
+
+    let result = std::panic::catch_unwind(|| {
+        suggest_amendment(&user.address, &dao.address, &dao.voter_card_address, "0", &"a".repeat(101),
+                          "Vec<Enum>(Enum(\"AllowMinting\", Decimal(\"5\")))");
+    });
+    assert!(result.is_err());
+
+    let result = std::panic::catch_unwind(|| {
+        make_multiple_choice_proposal(&user.address, &dao.address, &dao.voter_card_address, &"a".repeat(101),
+                                      "Vec<Struct>(Struct(\"Mint\", Vec<Enum>(Enum(\"AllowMinting\", Decimal(\"5\")))), Struct(\"Do nothing\", Vec<Enum>()))",
+                                      "Plurality");
+    });
+    assert!(result.is_err());
+}
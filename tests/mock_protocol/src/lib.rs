@@ -0,0 +1,63 @@
+//! Mock of an external protocol the Styx DAO can interact with through `Change::CallMethod`.
+//! It is only used by the integration tests.
+
+use scrypto::prelude::*;
+
+blueprint! {
+    struct MockProtocol {
+
+        /// Vault containing the assets deposited in the protocol
+        deposits: HashMap<ResourceAddress, Vault>,
+
+        /// Vault containing the reward tokens of the protocol
+        rewards: Vault
+    }
+
+    impl MockProtocol {
+
+        /// Instantiates a MockProtocol with a supply of reward tokens
+        pub fn instantiate() -> ComponentAddress {
+            let rewards = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_MAXIMUM)
+                .metadata("name", "Reward")
+                .initial_supply(dec!(1000000));
+
+            info!("reward : {}", rewards.resource_address());
+
+            Self {
+                deposits: HashMap::new(),
+                rewards: Vault::with_bucket(rewards)
+            }
+            .instantiate()
+            .globalize()
+        }
+
+        /// Keeps the given buckets and returns an amount of reward tokens
+        ///
+        /// # Arguments
+        /// * `buckets` - buckets to deposit
+        /// * `reward` - amount of reward tokens to return
+        pub fn deposit(&mut self, buckets: Vec<Bucket>, reward: Decimal) -> Vec<Bucket> {
+            self.keep(buckets);
+            vec![self.rewards.take(reward)]
+        }
+
+        /// Keeps the given buckets and returns nothing
+        ///
+        /// # Arguments
+        /// * `buckets` - buckets to donate
+        pub fn donate(&mut self, buckets: Vec<Bucket>) {
+            self.keep(buckets);
+        }
+
+        fn keep(&mut self, buckets: Vec<Bucket>) {
+            for bucket in buckets {
+                let address = bucket.resource_address();
+                match self.deposits.get_mut(&address) {
+                    None => { self.deposits.insert(address, Vault::with_bucket(bucket)); }
+                    Some(vault) => { vault.put(bucket); }
+                }
+            }
+        }
+    }
+}